@@ -5,15 +5,14 @@
  * LICENSE file in the root directory of this source tree.
  */
 
-#![feature(btree_cursors)]
-
-mod access;
-mod histogram;
-mod shards;
-mod curve;
-mod cache;
+use std::{
+	io::Error,
+	str::FromStr,
+	sync::Arc,
+};
 
 use clap::Parser;
+use rayon::prelude::*;
 
 use kwik::{
 	file::{
@@ -23,12 +22,42 @@ use kwik::{
 	progress::{Progress, Tag},
 };
 
-use crate::{
-	access::Access,
-	curve::Curve,
-	cache::CachePolicy,
+use kosmo_fast24::{
+	access::{Access, Key, Timestamp},
+	curve::{Curve, Interpolation},
+	curve_set::{CurveSet, CurveMetadata},
+	cache::{Cache, CachePolicy},
+	opt::OptCache,
+	grid::SizeGrid,
+	latency::{self, LatencyArgs},
 };
 
+// the number of accesses which are read from the trace before they are
+// simulated by each cache
+const CHUNK_SIZE: usize = 1_000_000;
+
+const DEFAULT_MEMORY_BUDGET: u64 = 16 * 1024 * 1024 * 1024;
+
+// the estimated memory (in bytes) of each object in a cache, including
+// the policy's metadata and its entry in the cache's map
+const OBJECT_MEMORY_OVERHEAD: u64 = 128;
+
+/// A cache policy, or Belady's offline optimal policy (which requires
+/// a pre-scan of the trace).
+#[derive(Clone)]
+enum AccuratePolicy {
+	Cache(CachePolicy),
+	Opt,
+}
+
+/// A policy along with the config from which it was parsed, which names
+/// its curve.
+#[derive(Clone)]
+struct NamedPolicy {
+	name: String,
+	policy: AccuratePolicy,
+}
+
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Args {
@@ -38,22 +67,51 @@ struct Args {
 	#[arg(short, long)]
 	wss: u64,
 
+	/// The policy (a cache policy or opt), which is recorded in the
+	/// metadata of the output.
 	#[arg(short = 'e', long)]
-	policy: CachePolicy,
+	policy: NamedPolicy,
 
+	/// The output path of the curve. A .json path is saved with the
+	/// configuration of the run; otherwise, the curve is saved as CSV rows
+	/// of <cache size>,<miss ratio>.
 	#[arg(short, long)]
 	output: String,
+
+	/// Saves a CSV curve with the configuration of the run (as comments)
+	/// and a header, as mrc saves its curves.
+	#[arg(long)]
+	with_metadata: bool,
+
+	/// The cache sizes to simulate (linear[-<points>], log[-<points>[-<min
+	/// size>]], or a path to a file with one size per line).
+	#[arg(short, long, default_value = "linear")]
+	grid: SizeGrid,
+
+	/// The memory (in bytes) available to the caches which are simulated
+	/// concurrently. Cache sizes which do not fit are simulated in later
+	/// passes over the trace.
+	#[arg(short = 'b', long, default_value_t = DEFAULT_MEMORY_BUDGET)]
+	memory_budget: u64,
+
+	#[command(flatten)]
+	latency: LatencyArgs,
 }
 
 fn main() {
 	let args = Args::parse();
 
+	let policy = &args.policy.policy;
+
 	let mut curve = Curve::default();
-	let step_size = if args.wss > 100 { args.wss / 100 } else { 1 };
+	let cache_sizes = args.grid.sizes(args.wss);
 
-	let cache_sizes = (step_size..=args.wss)
-		.step_by(step_size as usize)
-		.collect::<Vec<u64>>();
+	let metadata = CurveMetadata {
+		trace_path: Some(args.path.clone()),
+		algorithm: Some("accurate".to_string()),
+		policy: Some(args.policy.name.clone()),
+		..CurveMetadata::new()
+	};
 
 	let Ok(reader) = BinaryReader::<Access>::from_path(&args.path) else {
 		panic!("Invalid path.");
@@ -61,37 +119,196 @@ fn main() {
 
 	println!("{}", args.path);
 
-	let mut progress = Progress::new(reader.size() * cache_sizes.len() as u64)
+	let next_uses = match policy {
+		AccuratePolicy::Opt => Some(Arc::new(get_next_uses(&args.path))),
+		_ => None,
+	};
+
+	let groups = group_cache_sizes(
+		&cache_sizes,
+		get_mean_object_size(&args.path),
+		args.memory_budget,
+	);
+
+	let mut progress = Progress::new(reader.size() * groups.len() as u64)
 		.with_tag(Tag::Tps)
 		.with_tag(Tag::Eta)
 		.with_tag(Tag::Time);
 
-	// Loop through all cache sizes individually and simulate them one-by-one.
-	// We could do this in parallel, but the memory overhead is too large.
-	for cache_size in &cache_sizes {
-		let mut cache = args.policy.new_cache(*cache_size);
+	// Each group of cache sizes is simulated in one pass over the trace. The
+	// trace is read in chunks, and each chunk is simulated by the caches of
+	// the group in parallel.
+	for group in &groups {
+		let mut caches = group
+			.iter()
+			.map(|cache_size| policy.new_cache(*cache_size, next_uses.as_ref()))
+			.collect::<Vec<Box<dyn Cache>>>();
 
-		let Ok(reader) = BinaryReader::<Access>::from_path(&args.path) else {
+		let Ok(mut reader) = BinaryReader::<Access>::from_path(&args.path) else {
 			panic!("Invalid path.");
 		};
 
+		let mut chunk = Vec::<Access>::with_capacity(CHUNK_SIZE);
 		let mut count: u64 = 0;
 
-		for mut access in reader {
-			if access.is_valid_self_populating() {
-				access.timestamp = count + 1;
-				count += 1;
+		loop {
+			let mut num_read: usize = 0;
 
-				cache.handle_self_populating(&access);
+			chunk.clear();
+
+			for mut access in reader.by_ref().take(CHUNK_SIZE) {
+				if access.is_valid_self_populating() {
+					access.timestamp = count + 1;
+					count += 1;
+
+					chunk.push(access);
+				}
+
+				progress.tick(Access::chunk_size());
+				num_read += 1;
+			}
+
+			if num_read == 0 {
+				break;
 			}
 
-			progress.tick(Access::chunk_size());
+			caches
+				.par_iter_mut()
+				.for_each(|cache| {
+					for access in &chunk {
+						cache.handle_self_populating(access);
+					}
+				});
 		}
 
-		curve.add(cache.size(), cache.miss_ratio());
+		for cache in &caches {
+			curve.add(cache.size(), cache.miss_ratio());
+		}
 
-		if curve.to_file(&args.output).is_err() {
+		if save_curve(&args, &curve, &metadata).is_err() {
 			println!("Could not save curve to storage.");
 		}
 	}
+
+	if let (Some(latency_output), Some(latency_model)) = (&args.latency.latency_output, args.latency.model()) {
+		let performances = latency_model.performances(&curve, Interpolation::Step);
+
+		if latency::to_file(latency_output, &[(args.policy.name.as_str(), performances)]).is_err() {
+			println!("Could not save latencies to storage.");
+		}
+	}
+}
+
+/// Saves the curve in the legacy two-column CSV format, unless the output
+/// is JSON or the metadata is requested.
+fn save_curve(args: &Args, curve: &Curve, metadata: &CurveMetadata) -> Result<(), Error> {
+	if !args.with_metadata && !args.output.ends_with(".json") {
+		return curve.to_file(&args.output);
+	}
+
+	let mut curve_set = CurveSet::new(metadata.clone());
+	curve_set.add(&args.policy.name, curve.clone());
+
+	curve_set.to_file(&args.output)
+}
+
+impl AccuratePolicy {
+	fn new_cache(&self, size: u64, next_uses: Option<&Arc<Vec<Timestamp>>>) -> Box<dyn Cache> {
+		match self {
+			AccuratePolicy::Cache(policy) => policy.new_cache(size),
+
+			AccuratePolicy::Opt => {
+				let next_uses = next_uses.expect("Next uses must be computed for OPT.");
+				Box::new(OptCache::new(size, next_uses.clone()))
+			},
+		}
+	}
+}
+
+impl FromStr for AccuratePolicy {
+	type Err = Error;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match value {
+			"opt" => Ok(AccuratePolicy::Opt),
+			value => CachePolicy::from_str(value).map(AccuratePolicy::Cache),
+		}
+	}
+}
+
+impl FromStr for NamedPolicy {
+	type Err = Error;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		Ok(NamedPolicy {
+			name: value.to_string(),
+			policy: AccuratePolicy::from_str(value)?,
+		})
+	}
+}
+
+/// Pre-scans the trace to compute the next use of each valid access.
+fn get_next_uses(path: &str) -> Vec<Timestamp> {
+	let Ok(reader) = BinaryReader::<Access>::from_path(path) else {
+		panic!("Invalid path.");
+	};
+
+	let keys = reader
+		.filter(|access| access.is_valid_self_populating())
+		.map(|access| access.key)
+		.collect::<Vec<Key>>();
+
+	OptCache::next_uses(&keys)
+}
+
+/// Returns the mean size of the objects of the first chunk of the trace,
+/// which is used to estimate the memory of each cache.
+fn get_mean_object_size(path: &str) -> u64 {
+	let Ok(reader) = BinaryReader::<Access>::from_path(path) else {
+		panic!("Invalid path.");
+	};
+
+	let sizes = reader
+		.take(CHUNK_SIZE)
+		.filter(|access| access.is_valid_self_populating())
+		.map(|access| access.size as u64)
+		.collect::<Vec<u64>>();
+
+	match sizes.is_empty() {
+		true => 1,
+		false => (sizes.iter().sum::<u64>() / sizes.len() as u64).max(1),
+	}
+}
+
+/// Splits the (ascending) cache sizes into groups whose estimated memory
+/// fits in the budget. Every group has at least one cache size, even if
+/// it alone exceeds the budget.
+fn group_cache_sizes(
+	cache_sizes: &[u64],
+	mean_object_size: u64,
+	memory_budget: u64,
+) -> Vec<Vec<u64>> {
+	let mut groups = Vec::<Vec<u64>>::new();
+	let mut group = Vec::<u64>::new();
+	let mut group_memory: u64 = 0;
+
+	for cache_size in cache_sizes {
+		let memory = cache_size.div_ceil(mean_object_size) * OBJECT_MEMORY_OVERHEAD;
+
+		if !group.is_empty() && group_memory + memory > memory_budget {
+			groups.push(group);
+
+			group = Vec::new();
+			group_memory = 0;
+		}
+
+		group.push(*cache_size);
+		group_memory += memory;
+	}
+
+	if !group.is_empty() {
+		groups.push(group);
+	}
+
+	groups
 }
@@ -5,6 +5,10 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use std::io::{Error, Read, Write};
+
+use serde::{Serialize, Deserialize};
+
 use crate::{
 	access::{Access, Timestamp, Key, Size},
 	curve::Curve,
@@ -37,6 +41,10 @@ pub trait Algorithm {
 	/// Returns the MRC.
 	fn curve(&mut self) -> Curve;
 
+	/// Returns the MRC of each configured policy, in the order in which
+	/// the policies were configured.
+	fn curves(&mut self) -> Vec<Curve>;
+
 	/// Returns `true` if we should processes the supplied access.
 	fn verify_access(&self, access: &Access) -> bool {
 		access.is_valid_self_populating()
@@ -44,8 +52,16 @@ pub trait Algorithm {
 
 	/// Returns `true` if the supplied access should be sampled.
 	fn verify_shards(&mut self, _: &Access) -> bool;
+
+	/// Writes the state of the algorithm to a checkpoint.
+	fn save(&self, _: &mut dyn Write) -> Result<(), Error>;
+
+	/// Restores the state of the algorithm from a checkpoint. The algorithm
+	/// must be configured as it was when the checkpoint was saved.
+	fn restore(&mut self, _: &mut dyn Read) -> Result<(), Error>;
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct Object {
 	pub timestamp: Timestamp,
 	pub key: Key,
@@ -73,3 +89,11 @@ impl PartialEq for Object {
 }
 
 impl Eq for Object {}
+
+/// Returns `true` if any two of the supplied values are equal.
+pub(crate) fn has_duplicates<T: PartialEq>(values: &[T]) -> bool {
+	values
+		.iter()
+		.enumerate()
+		.any(|(index, value)| values[index + 1..].contains(value))
+}
@@ -0,0 +1,112 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use clap::Parser;
+use kwik::fmt;
+
+use kosmo_fast24::{
+	curve::{Curve, Interpolation},
+	curve_set,
+	allocation::{self, AllocationMethod},
+};
+
+// the number of allocation units of the budget if no granularity is
+// supplied
+const DEFAULT_NUM_UNITS: u64 = 1_000;
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+	/// The curve file of each tenant.
+	#[arg(short, long, num_args = 1.., required = true)]
+	path: Vec<String>,
+
+	/// The request rate of each tenant, in the order of the paths (all
+	/// tenants have the same rate by default).
+	#[arg(short, long, num_args = 1..)]
+	weight: Vec<f64>,
+
+	/// The total memory (in bytes) which is split between the tenants.
+	#[arg(short, long)]
+	budget: u64,
+
+	/// The size (in bytes) of each unit of allocated memory (the budget /
+	/// 1000 by default).
+	#[arg(short, long)]
+	granularity: Option<u64>,
+
+	/// The search used to split the budget (dp or hull).
+	#[arg(short, long, default_value = "dp")]
+	method: AllocationMethod,
+
+	/// The interpolation between the points of the curves (step or
+	/// linear), which is used by the dp method.
+	#[arg(short, long, default_value = "step")]
+	interpolation: Interpolation,
+}
+
+fn main() {
+	let args = Args::parse();
+
+	let curves = args.path
+		.iter()
+		.map(|path| {
+			Curve::from_file(path)
+				.unwrap_or_else(|err| panic!("Could not load curve {path}: {err}"))
+		})
+		.collect::<Vec<Curve>>();
+
+	let weights = match args.weight.is_empty() {
+		true => vec![1.0; curves.len()],
+		false => args.weight.clone(),
+	};
+
+	assert_eq!(weights.len(), curves.len(), "Each tenant must have a weight.");
+
+	let granularity = args.granularity
+		.unwrap_or(args.budget / DEFAULT_NUM_UNITS)
+		.max(1);
+
+	let allocation = allocation::allocate(
+		&curves,
+		&weights,
+		args.budget,
+		granularity,
+		args.method,
+		args.interpolation,
+	);
+
+	let labels = args.path
+		.iter()
+		.map(|path| curve_set::get_file_label(path))
+		.collect::<Vec<String>>();
+
+	let label_width = labels
+		.iter()
+		.map(|label| label.len())
+		.chain(["Tenant".len()])
+		.max()
+		.unwrap();
+
+	println!(
+		"{:<label_width$}  {:>10}  {:>14}  {:>16}  {:>10}",
+		"Tenant", "Weight", "Size", "Size (B)", "Miss ratio",
+	);
+
+	for (index, label) in labels.iter().enumerate() {
+		println!(
+			"{:<label_width$}  {:>10.4}  {:>14}  {:>16}  {:>10.6}",
+			label,
+			weights[index],
+			fmt::memory(allocation.sizes[index], Some(2)),
+			allocation.sizes[index],
+			allocation.miss_ratios[index],
+		);
+	}
+
+	println!("Aggregate miss ratio: {:.6}", allocation.aggregate_miss_ratio);
+}
@@ -0,0 +1,307 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::{
+	io::{Error, ErrorKind},
+	str::FromStr,
+};
+
+use crate::curve::{Curve, Interpolation};
+
+/// The largest number of allocation units into which a budget is split,
+/// which bounds the time of the (quadratic) dynamic program.
+pub const MAX_NUM_UNITS: u64 = 10_000;
+
+/// The search used to split memory between tenants.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AllocationMethod {
+	/// An exact dynamic program over the (interpolated) curves, which
+	/// takes time quadratic in the number of allocation units.
+	#[default]
+	DynamicProgram,
+
+	/// A greedy search over the convex hulls of the curves, which takes
+	/// time linear in the number of allocation units. The resulting miss
+	/// ratios are those of the hulls, which can be achieved by Talus-style
+	/// partitioning of each tenant's share.
+	ConvexHull,
+}
+
+/// The split of a memory budget between tenants.
+#[derive(Clone, Debug)]
+pub struct Allocation {
+	/// The size allocated to each tenant.
+	pub sizes: Vec<u64>,
+
+	/// The expected miss ratio of each tenant at its allocated size.
+	pub miss_ratios: Vec<f64>,
+
+	/// The expected miss ratio of all requests, where each tenant's miss
+	/// ratio is weighted by its request rate.
+	pub aggregate_miss_ratio: f64,
+}
+
+/// Splits the budget between the tenants, whose MRCs and request-rate
+/// weights are supplied, minimizing the total (weighted) misses. Memory
+/// is allocated in units of the supplied granularity, so any remainder of
+/// the budget smaller than one unit is left unallocated. The budget may be
+/// at most `MAX_NUM_UNITS` units.
+pub fn allocate(
+	curves: &[Curve],
+	weights: &[f64],
+	budget: u64,
+	granularity: u64,
+	method: AllocationMethod,
+	interpolation: Interpolation,
+) -> Allocation {
+	assert!(!curves.is_empty(), "There must be at least one tenant.");
+	assert_eq!(curves.len(), weights.len(), "Each tenant must have a weight.");
+	assert!(weights.iter().all(|weight| *weight >= 0.0), "Weights must be non-negative.");
+	assert!(granularity > 0, "The allocation granularity must be positive.");
+
+	assert!(
+		budget / granularity <= MAX_NUM_UNITS,
+		"The budget cannot be split into more than {MAX_NUM_UNITS} units (the granularity must be at least {}).",
+		budget.div_ceil(MAX_NUM_UNITS),
+	);
+
+	let num_units = (budget / granularity) as usize;
+
+	let (curves, interpolation) = match method {
+		AllocationMethod::DynamicProgram => (curves.to_vec(), interpolation),
+
+		AllocationMethod::ConvexHull => (
+			curves.iter().map(Curve::convex_hull).collect(),
+			Interpolation::Linear,
+		),
+	};
+
+	// the weighted misses of each tenant at each number of units
+	let costs = curves
+		.iter()
+		.zip(weights)
+		.map(|(curve, weight)| {
+			(0..=num_units)
+				.map(|units| weight * curve.get_interpolated_miss_ratio(units as u64 * granularity, interpolation))
+				.collect::<Vec<f64>>()
+		})
+		.collect::<Vec<Vec<f64>>>();
+
+	let units = match method {
+		AllocationMethod::DynamicProgram => allocate_dynamic_program(&costs, num_units),
+		AllocationMethod::ConvexHull => allocate_greedy(&costs, num_units),
+	};
+
+	let sizes = units
+		.iter()
+		.map(|units| *units as u64 * granularity)
+		.collect::<Vec<u64>>();
+
+	let miss_ratios = curves
+		.iter()
+		.zip(&sizes)
+		.map(|(curve, size)| curve.get_interpolated_miss_ratio(*size, interpolation))
+		.collect::<Vec<f64>>();
+
+	let total_weight = weights.iter().sum::<f64>();
+
+	let aggregate_miss_ratio = match total_weight > 0.0 {
+		true => miss_ratios
+			.iter()
+			.zip(weights)
+			.map(|(miss_ratio, weight)| miss_ratio * weight)
+			.sum::<f64>() / total_weight,
+
+		false => 0.0,
+	};
+
+	Allocation {
+		sizes,
+		miss_ratios,
+		aggregate_miss_ratio,
+	}
+}
+
+/// Returns the number of units of each tenant which minimizes the total
+/// cost, where `costs[tenant][units]` is the cost of the tenant with the
+/// number of units.
+fn allocate_dynamic_program(costs: &[Vec<f64>], num_units: usize) -> Vec<usize> {
+	// the minimum cost of the tenants so far using at most each number of
+	// units, and the units given to each tenant to reach it
+	let mut min_costs = vec![0.0_f64; num_units + 1];
+	let mut choices = Vec::<Vec<usize>>::with_capacity(costs.len());
+
+	for tenant_costs in costs {
+		let mut next_min_costs = vec![f64::INFINITY; num_units + 1];
+		let mut tenant_choices = vec![0; num_units + 1];
+
+		for total_units in 0..=num_units {
+			for units in 0..=total_units {
+				let cost = min_costs[total_units - units] + tenant_costs[units];
+
+				if cost < next_min_costs[total_units] {
+					next_min_costs[total_units] = cost;
+					tenant_choices[total_units] = units;
+				}
+			}
+		}
+
+		min_costs = next_min_costs;
+		choices.push(tenant_choices);
+	}
+
+	let mut remaining_units = num_units;
+	let mut units = vec![0; costs.len()];
+
+	for (tenant, tenant_choices) in choices.iter().enumerate().rev() {
+		units[tenant] = tenant_choices[remaining_units];
+		remaining_units -= units[tenant];
+	}
+
+	units
+}
+
+/// Returns the number of units of each tenant, giving each unit to the
+/// tenant whose cost decreases the most. This is optimal if the costs are
+/// convex (e.g., those of convex hulls).
+fn allocate_greedy(costs: &[Vec<f64>], num_units: usize) -> Vec<usize> {
+	let mut units = vec![0; costs.len()];
+
+	for _ in 0..num_units {
+		let best = costs
+			.iter()
+			.enumerate()
+			.map(|(tenant, tenant_costs)| {
+				let current = units[tenant];
+				(tenant, tenant_costs[current] - tenant_costs[current + 1])
+			})
+			.max_by(|(_, gain), (_, other_gain)| gain.total_cmp(other_gain));
+
+		match best {
+			Some((tenant, gain)) if gain > 0.0 => units[tenant] += 1,
+			_ => break,
+		}
+	}
+
+	units
+}
+
+impl FromStr for AllocationMethod {
+	type Err = Error;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match value {
+			"dp" => Ok(AllocationMethod::DynamicProgram),
+			"hull" => Ok(AllocationMethod::ConvexHull),
+
+			_ => Err(Error::new(
+				ErrorKind::InvalidData,
+				"Invalid allocation method (expected dp or hull).",
+			)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		allocation::{allocate, AllocationMethod},
+		curve::{Curve, Interpolation},
+		test_utils::assert_close,
+	};
+
+	fn curves() -> Vec<Curve> {
+		vec![
+			// benefits from a small cache, but not from more
+			Curve::from_points(&[(0, 1.0), (100, 0.2), (200, 0.2), (300, 0.2), (400, 0.2)]),
+
+			// benefits evenly from each unit of cache
+			Curve::from_points(&[(0, 1.0), (100, 0.75), (200, 0.5), (300, 0.25), (400, 0.0)]),
+		]
+	}
+
+	#[test]
+	fn dynamic_program_minimizes_misses() {
+		let allocation = allocate(
+			&curves(),
+			&[1.0, 1.0],
+			300,
+			100,
+			AllocationMethod::DynamicProgram,
+			Interpolation::Step,
+		);
+
+		assert_eq!(allocation.sizes, vec![100, 200]);
+		assert_close(allocation.aggregate_miss_ratio, 0.35);
+	}
+
+	#[test]
+	fn weights_shift_memory() {
+		let allocation = allocate(
+			&curves(),
+			&[0.01, 1.0],
+			300,
+			100,
+			AllocationMethod::DynamicProgram,
+			Interpolation::Step,
+		);
+
+		assert_eq!(allocation.sizes, vec![0, 300]);
+	}
+
+	#[test]
+	fn greedy_matches_dynamic_program_on_convex_curves() {
+		for budget in [0, 100, 200, 400, 800] {
+			let dynamic_program = allocate(
+				&curves(),
+				&[1.0, 2.0],
+				budget,
+				50,
+				AllocationMethod::DynamicProgram,
+				Interpolation::Linear,
+			);
+
+			let greedy = allocate(
+				&curves(),
+				&[1.0, 2.0],
+				budget,
+				50,
+				AllocationMethod::ConvexHull,
+				Interpolation::Linear,
+			);
+
+			assert!(greedy.sizes.iter().sum::<u64>() <= budget);
+			assert_close(dynamic_program.aggregate_miss_ratio, greedy.aggregate_miss_ratio);
+		}
+	}
+
+	#[test]
+	fn greedy_hull_includes_origin() {
+		// the first tenant's curve is not convex once the line from a miss
+		// ratio of 1.0 at size zero to its first point is included
+		let curves = vec![
+			Curve::from_points(&[(200, 0.95), (300, 0.0)]),
+			Curve::from_points(&[(100, 0.6), (200, 0.5), (300, 0.45)]),
+		];
+
+		let dynamic_program = allocate(&curves, &[1.0, 1.0], 300, 100, AllocationMethod::DynamicProgram, Interpolation::Linear);
+		let greedy = allocate(&curves, &[1.0, 1.0], 300, 100, AllocationMethod::ConvexHull, Interpolation::Linear);
+
+		assert_eq!(dynamic_program.sizes, vec![300, 0]);
+		assert_eq!(greedy.sizes, vec![200, 100]);
+
+		// the hull is at or below the curve, so the greedy search over the
+		// hulls is no worse than the dynamic program over the curves
+		assert!(greedy.aggregate_miss_ratio <= dynamic_program.aggregate_miss_ratio + 1e-9);
+	}
+
+	#[test]
+	#[should_panic(expected = "The budget cannot be split")]
+	fn too_many_units_are_rejected() {
+		allocate(&curves(), &[1.0, 1.0], 1 << 40, 1, AllocationMethod::DynamicProgram, Interpolation::Step);
+	}
+}
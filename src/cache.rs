@@ -11,9 +11,25 @@ mod lfu_cache;
 mod fifo_cache;
 mod two_q_cache;
 mod lrfu_cache;
+mod sieve_cache;
+mod s3_fifo_cache;
+mod arc_cache;
+mod lirs_cache;
+mod gds_cache;
+mod w_tiny_lfu_cache;
+mod lru_k_cache;
+mod slru_cache;
+mod plugin;
+
+use std::io::{Error, ErrorKind, Read, Write};
+
+use serde::{Serialize, Deserialize};
 
 use crate::access::{Access, Key, Size};
-pub use crate::cache::policy::CachePolicy;
+pub use crate::cache::{
+	policy::CachePolicy,
+	plugin::{CachePluginPolicy, register_plugin, find_plugin},
+};
 
 /// A cache (used by MiniSim and accurate).
 pub trait Cache: Send + Sync {
@@ -86,16 +102,32 @@ pub trait Cache: Send + Sync {
 	fn reduce(&mut self, _: u64);
 	fn resize(&mut self, _: u64);
 	fn rescale(&mut self, _: f64);
+
+	/// Writes the state of the cache to a checkpoint.
+	fn save(&self, _: &mut dyn Write) -> Result<(), Error> {
+		Err(Error::new(
+			ErrorKind::Unsupported,
+			"The cache does not support checkpoints.",
+		))
+	}
+
+	/// Restores the state of the cache from a checkpoint.
+	fn restore(&mut self, _: &mut dyn Read) -> Result<(), Error> {
+		Err(Error::new(
+			ErrorKind::Unsupported,
+			"The cache does not support checkpoints.",
+		))
+	}
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Object {
 	pub key: Key,
 	pub size: Size,
 }
 
 impl Object {
-	fn new(access: &Access) -> Self {
+	pub fn new(access: &Access) -> Self {
 		Object {
 			key: access.key,
 			size: access.size,
@@ -117,4 +149,12 @@ pub use crate::{
 	cache::two_q_cache::*,
 	cache::lru_cache::*,
 	cache::lrfu_cache::*,
+	cache::sieve_cache::*,
+	cache::s3_fifo_cache::*,
+	cache::arc_cache::*,
+	cache::lirs_cache::*,
+	cache::gds_cache::*,
+	cache::w_tiny_lfu_cache::*,
+	cache::lru_k_cache::*,
+	cache::slru_cache::*,
 };
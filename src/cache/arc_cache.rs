@@ -0,0 +1,432 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::io::{Error, Read, Write};
+use std::cmp;
+
+use rustc_hash::FxHashMap;
+use dlv_list::{VecList, Index};
+use serde::{Serialize, Deserialize};
+
+use crate::{
+	cache::{Cache, Object},
+	access::{Access, Key},
+	checkpoint,
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct ArcCache {
+	max_size: u64,
+
+	// the target size of T1 (in bytes)
+	p: u64,
+
+	count: f64,
+	hits: f64,
+
+	#[serde(skip)]
+	map: FxHashMap<Key, StackIndex>,
+
+	t1: Stack,
+	t2: Stack,
+	b1: Stack,
+	b2: Stack,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Stack {
+	stack: VecList<Object>,
+	size: u64,
+}
+
+enum StackIndex {
+	T1(Index<Object>),
+	T2(Index<Object>),
+	B1(Index<Object>),
+	B2(Index<Object>),
+}
+
+impl ArcCache {
+	pub fn new(size: u64) -> Self {
+		ArcCache {
+			max_size: size,
+
+			p: 0,
+
+			count: 0.0,
+			hits: 0.0,
+
+			map: FxHashMap::default(),
+
+			t1: Stack::default(),
+			t2: Stack::default(),
+			b1: Stack::default(),
+			b2: Stack::default(),
+		}
+	}
+}
+
+impl Cache for ArcCache {
+	fn size(&self) -> u64 {
+		self.max_size
+	}
+
+	fn miss_ratio(&self) -> f64 {
+		if self.count > 0.0 {
+			return 1.0 - self.hits / self.count;
+		}
+
+		0.0
+	}
+
+	fn increment_count(&mut self) {
+		self.count += 1.0
+	}
+
+	fn increment_hits(&mut self) {
+		self.hits += 1.0
+	}
+
+	fn clear_counters(&mut self) {
+		self.count = 0.0;
+		self.hits = 0.0;
+	}
+
+	fn process_get(&mut self, access: &Access) -> bool {
+		let object = match self.map.get(&access.key) {
+			Some(StackIndex::T1(index)) => self.t1.remove(*index).unwrap(),
+			Some(StackIndex::T2(index)) => self.t2.remove(*index).unwrap(),
+			_ => return false,
+		};
+
+		let index = self.t2.push_front(object);
+		self.map.insert(access.key, StackIndex::T2(index));
+
+		true
+	}
+
+	fn process_set(&mut self, access: &Access) {
+		if access.size as u64 > self.max_size || self.has(access.key) {
+			return;
+		}
+
+		let object = Object::new(access);
+		let object_size = access.size as u64;
+
+		let stack_index = match self.map.remove(&access.key) {
+			Some(StackIndex::B1(index)) => {
+				// the ghost is counted in the size of B1 when adapting p
+				let delta = cmp::max(self.b2.size / cmp::max(self.b1.size, 1), 1) * object_size;
+				self.p = cmp::min(self.p + delta, self.max_size);
+
+				self.b1.remove(index);
+
+				self.replace(self.max_size - object_size, false);
+				StackIndex::T2(self.t2.push_front(object))
+			},
+
+			Some(StackIndex::B2(index)) => {
+				let delta = cmp::max(self.b1.size / cmp::max(self.b2.size, 1), 1) * object_size;
+				self.p = self.p.saturating_sub(delta);
+
+				self.b2.remove(index);
+
+				self.replace(self.max_size - object_size, true);
+				StackIndex::T2(self.t2.push_front(object))
+			},
+
+			_ => {
+				self.reduce(self.max_size - object_size);
+				StackIndex::T1(self.t1.push_front(object))
+			},
+		};
+
+		self.map.insert(access.key, stack_index);
+		self.trim_ghosts();
+	}
+
+	fn process_del(&mut self, key: Key) {
+		let Some(stack_index) = self.map.remove(&key) else {
+			return;
+		};
+
+		match stack_index {
+			StackIndex::T1(index) => self.t1.remove(index),
+			StackIndex::T2(index) => self.t2.remove(index),
+			StackIndex::B1(index) => self.b1.remove(index),
+			StackIndex::B2(index) => self.b2.remove(index),
+		};
+	}
+
+	fn process_has(&self, key: Key) -> bool {
+		matches!(
+			self.map.get(&key),
+			Some(StackIndex::T1(_)) | Some(StackIndex::T2(_)),
+		)
+	}
+
+	fn reduce(&mut self, target_size: u64) {
+		self.replace(target_size, false);
+	}
+
+	fn resize(&mut self, size: u64) {
+		self.reduce(size);
+
+		self.max_size = size;
+		self.p = cmp::min(self.p, size);
+
+		self.trim_ghosts();
+	}
+
+	fn rescale(&mut self, ratio: f64) {
+		self.count *= ratio;
+		self.hits *= ratio;
+	}
+
+	fn save(&self, writer: &mut dyn Write) -> Result<(), Error> {
+		checkpoint::save(writer, self)
+	}
+
+	fn restore(&mut self, reader: &mut dyn Read) -> Result<(), Error> {
+		*self = checkpoint::restore(reader)?;
+
+		// the indices of the objects change when the lists are restored,
+		// so the map is rebuilt rather than saved
+		for index in self.t1.stack.indices() {
+			self.map.insert(self.t1.stack.get(index).unwrap().key, StackIndex::T1(index));
+		}
+
+		for index in self.t2.stack.indices() {
+			self.map.insert(self.t2.stack.get(index).unwrap().key, StackIndex::T2(index));
+		}
+
+		for index in self.b1.stack.indices() {
+			self.map.insert(self.b1.stack.get(index).unwrap().key, StackIndex::B1(index));
+		}
+
+		for index in self.b2.stack.indices() {
+			self.map.insert(self.b2.stack.get(index).unwrap().key, StackIndex::B2(index));
+		}
+
+		Ok(())
+	}
+}
+
+impl ArcCache {
+	fn current_size(&self) -> u64 {
+		self.t1.size + self.t2.size
+	}
+
+	/// Moves objects from T1 to B1 or from T2 to B2 until the resident
+	/// objects fit in the target size. T1 is reduced while it exceeds
+	/// its target size, p.
+	fn replace(&mut self, target_size: u64, in_b2: bool) {
+		while self.current_size() > target_size {
+			let reduce_t1 = !self.t1.is_empty() && (
+				self.t1.size > self.p
+					|| in_b2 && self.t1.size == self.p
+					|| self.t2.is_empty()
+			);
+
+			let (key, stack_index) = match reduce_t1 {
+				true => {
+					let object = self.t1.pop_back().unwrap();
+					let key = object.key;
+
+					(key, StackIndex::B1(self.b1.push_front(object)))
+				},
+
+				false => {
+					let object = self.t2.pop_back().unwrap();
+					let key = object.key;
+
+					(key, StackIndex::B2(self.b2.push_front(object)))
+				},
+			};
+
+			self.map.insert(key, stack_index);
+		}
+	}
+
+	/// Evicts ghost objects such that T1 and B1 together do not exceed
+	/// the cache size and all four lists together do not exceed twice
+	/// the cache size.
+	fn trim_ghosts(&mut self) {
+		while !self.b1.is_empty() && self.t1.size + self.b1.size > self.max_size {
+			self.evict_b1();
+		}
+
+		while self.current_size() + self.b1.size + self.b2.size > 2 * self.max_size {
+			if !self.b2.is_empty() {
+				self.evict_b2();
+			} else if !self.b1.is_empty() {
+				self.evict_b1();
+			} else {
+				break;
+			}
+		}
+	}
+
+	fn evict_b1(&mut self) {
+		if let Some(object) = self.b1.pop_back() {
+			self.map.remove(&object.key);
+		}
+	}
+
+	fn evict_b2(&mut self) {
+		if let Some(object) = self.b2.pop_back() {
+			self.map.remove(&object.key);
+		}
+	}
+}
+
+impl Stack {
+	fn is_empty(&self) -> bool {
+		self.stack.is_empty()
+	}
+
+	fn remove(&mut self, index: Index<Object>) -> Option<Object> {
+		let object = self.stack.remove(index);
+
+		if let Some(object) = &object {
+			self.size -= object.size as u64;
+		}
+
+		object
+	}
+
+	fn push_front(&mut self, object: Object) -> Index<Object> {
+		self.size += object.size as u64;
+		self.stack.push_front(object)
+	}
+
+	fn pop_back(&mut self) -> Option<Object> {
+		let object = self.stack.pop_back();
+
+		if let Some(object) = &object {
+			self.size -= object.size as u64;
+		}
+
+		object
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		access::{Access, Command},
+		cache::{Cache, ArcCache},
+	};
+
+	fn access(timestamp: u64, key: u64) -> Access {
+		Access {
+			timestamp,
+			command: Command::Get,
+			key,
+			size: 1,
+			ttl: None,
+		}
+	}
+
+	fn handle(cache: &mut ArcCache, keys: &[u64]) {
+		for (index, key) in keys.iter().enumerate() {
+			cache.handle_self_populating(&access(index as u64 + 1, *key));
+		}
+	}
+
+	#[test]
+	fn ghost_hits_adapt_target() {
+		let mut cache = ArcCache::new(4);
+
+		// 1 and 2 are moved to T2 by their second accesses, and 3 is moved
+		// to B1 by the insertion of 5
+		handle(&mut cache, &[1, 2, 1, 2, 3, 4, 5]);
+
+		assert_eq!(cache.p, 0);
+		assert_eq!(cache.b1.size, 1);
+		assert!(!cache.has(3));
+
+		// a hit in B1 grows the target size of T1
+		handle(&mut cache, &[3]);
+
+		assert_eq!(cache.p, 1);
+		assert!(cache.has(3));
+		assert_eq!(cache.t2.size, 3);
+
+		// 1 is moved from T2 to B2 by the insertion of 6, and a hit in B2
+		// shrinks the target size of T1
+		handle(&mut cache, &[6]);
+		assert_eq!(cache.b2.size, 1);
+
+		handle(&mut cache, &[1]);
+
+		assert_eq!(cache.p, 0);
+		assert!(cache.has(1));
+		assert_eq!(cache.current_size(), 4);
+	}
+
+	#[test]
+	fn ghost_hit_delta_counts_the_ghost() {
+		let mut cache = ArcCache::new(4);
+
+		// 1 and 2 are moved to B2 by the insertions of 5 and 6 while T1 is
+		// empty, and 6 is moved to B1 by the insertion of 7
+		handle(&mut cache, &[1, 2, 3, 4, 1, 2, 3, 4, 5, 5, 6, 7]);
+
+		assert_eq!(cache.b1.size, 1);
+		assert_eq!(cache.b2.size, 2);
+
+		// a hit on the single entry of B1 grows p by |B2| / |B1|
+		handle(&mut cache, &[6]);
+
+		assert_eq!(cache.p, 2);
+		assert!(cache.has(6));
+
+		let mut cache = ArcCache::new(4);
+
+		// 7 is also moved to B1, by the insertion of 8
+		handle(&mut cache, &[1, 2, 3, 4, 1, 2, 3, 4, 5, 5, 6, 7, 8]);
+
+		assert_eq!(cache.b1.size, 2);
+		assert_eq!(cache.b2.size, 2);
+
+		handle(&mut cache, &[6]);
+		assert_eq!(cache.p, 1);
+	}
+
+	#[test]
+	fn resize_and_reduce_bound_lists() {
+		let mut cache = ArcCache::new(8);
+
+		let keys = (0..200u64)
+			.map(|index| index * 7 % 13 + index % 3 * 5)
+			.collect::<Vec<u64>>();
+
+		handle(&mut cache, &keys);
+		assert_eq!(cache.current_size(), 8);
+
+		cache.resize(4);
+
+		assert_eq!(cache.size(), 4);
+		assert!(cache.current_size() <= 4);
+		assert!(cache.p <= 4);
+		assert!(cache.t1.size + cache.b1.size <= 4);
+		assert!(cache.current_size() + cache.b1.size + cache.b2.size <= 8);
+
+		cache.reduce(2);
+
+		assert!(cache.current_size() <= 2);
+		assert_eq!(cache.size(), 4);
+
+		cache.resize(16);
+		handle(&mut cache, &keys);
+
+		assert!(cache.current_size() <= 16);
+		assert!(cache.t1.size + cache.b1.size <= 16);
+		assert!(cache.current_size() + cache.b1.size + cache.b2.size <= 32);
+	}
+}
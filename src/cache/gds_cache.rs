@@ -0,0 +1,326 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::{
+	io::{Error, ErrorKind, Read, Write},
+	collections::BTreeSet,
+	str::FromStr,
+};
+
+use rustc_hash::FxHashMap;
+use serde::{Serialize, Deserialize};
+
+use crate::{
+	cache::{Cache, Object},
+	access::{Access, Key, Size},
+	checkpoint,
+};
+
+// the fixed latency of fetching an object (in ms)
+const LATENCY_BASE: f64 = 1.0;
+
+// the rate at which an object is transferred once fetched (in bytes per ms)
+const LATENCY_BANDWIDTH: f64 = 100_000.0;
+
+/// The cost of fetching an object into a GreedyDual-Size cache.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GdsCost {
+	/// Every object has the same cost.
+	Uniform,
+
+	/// The cost of an object is its size.
+	Size,
+
+	/// The cost of an object is the latency of fetching it, modelled
+	/// as a fixed latency plus a transfer time proportional to its size.
+	Latency,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GdsCache {
+	max_size: u64,
+	current_size: u64,
+
+	cost: GdsCost,
+	frequency: bool,
+
+	// the inflation value, L
+	inflation: f64,
+	sequence: u64,
+
+	count: f64,
+	hits: f64,
+
+	map: FxHashMap<Key, GdsObject>,
+	queue: BTreeSet<(Priority, u64, Key)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GdsObject {
+	object: Object,
+	frequency: u64,
+
+	priority: Priority,
+	sequence: u64,
+}
+
+/// A non-negative priority, ordered by its bit representation.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+struct Priority(u64);
+
+impl GdsCache {
+	/// Creates a GreedyDual-Size cache, or a GDSF cache if `frequency`
+	/// is `true`.
+	pub fn new(size: u64, cost: GdsCost, frequency: bool) -> Self {
+		GdsCache {
+			max_size: size,
+			current_size: 0,
+
+			cost,
+			frequency,
+
+			inflation: 0.0,
+			sequence: 0,
+
+			count: 0.0,
+			hits: 0.0,
+
+			map: FxHashMap::default(),
+			queue: BTreeSet::new(),
+		}
+	}
+}
+
+impl Cache for GdsCache {
+	fn size(&self) -> u64 {
+		self.max_size
+	}
+
+	fn miss_ratio(&self) -> f64 {
+		if self.count > 0.0 {
+			return 1.0 - self.hits / self.count;
+		}
+
+		0.0
+	}
+
+	fn increment_count(&mut self) {
+		self.count += 1.0
+	}
+
+	fn increment_hits(&mut self) {
+		self.hits += 1.0
+	}
+
+	fn clear_counters(&mut self) {
+		self.count = 0.0;
+		self.hits = 0.0;
+	}
+
+	fn process_get(&mut self, access: &Access) -> bool {
+		let Some(mut gds_object) = self.map.remove(&access.key) else {
+			return false;
+		};
+
+		self.queue.remove(&gds_object.queue_key());
+
+		gds_object.frequency += 1;
+		self.prioritize(&mut gds_object);
+
+		self.queue.insert(gds_object.queue_key());
+		self.map.insert(access.key, gds_object);
+
+		true
+	}
+
+	fn process_set(&mut self, access: &Access) {
+		if access.size as u64 > self.max_size || self.has(access.key) {
+			return;
+		}
+
+		self.reduce(self.max_size - access.size as u64);
+
+		let mut gds_object = GdsObject::new(access);
+		self.prioritize(&mut gds_object);
+
+		self.queue.insert(gds_object.queue_key());
+		self.map.insert(access.key, gds_object);
+
+		self.current_size += access.size as u64;
+	}
+
+	fn process_del(&mut self, key: Key) {
+		let Some(gds_object) = self.map.remove(&key) else {
+			return;
+		};
+
+		self.queue.remove(&gds_object.queue_key());
+		self.current_size -= gds_object.object.size as u64;
+	}
+
+	fn process_has(&self, key: Key) -> bool {
+		self.map.contains_key(&key)
+	}
+
+	fn reduce(&mut self, target_size: u64) {
+		while self.current_size > target_size {
+			let Some((priority, _, key)) = self.queue.pop_first() else {
+				return;
+			};
+
+			let gds_object = self.map.remove(&key).unwrap();
+
+			self.inflation = priority.value();
+			self.current_size -= gds_object.object.size as u64;
+		}
+	}
+
+	fn resize(&mut self, size: u64) {
+		self.reduce(size);
+		self.max_size = size;
+	}
+
+	fn rescale(&mut self, ratio: f64) {
+		self.count *= ratio;
+		self.hits *= ratio;
+	}
+
+	fn save(&self, writer: &mut dyn Write) -> Result<(), Error> {
+		checkpoint::save(writer, self)
+	}
+
+	fn restore(&mut self, reader: &mut dyn Read) -> Result<(), Error> {
+		*self = checkpoint::restore(reader)?;
+		Ok(())
+	}
+}
+
+impl GdsCache {
+	/// Sets the priority of the object to H = L + F * C / S, where F is
+	/// 1 unless the cache is frequency-weighted.
+	fn prioritize(&mut self, gds_object: &mut GdsObject) {
+		let frequency = match self.frequency {
+			true => gds_object.frequency,
+			false => 1,
+		};
+
+		let value = self.cost.value(gds_object.object.size, frequency);
+
+		gds_object.priority = Priority::new(self.inflation + value);
+		gds_object.sequence = self.sequence;
+
+		self.sequence += 1;
+	}
+}
+
+impl GdsCost {
+	/// Returns the cost of fetching an object of the supplied size.
+	pub fn cost(&self, size: Size) -> f64 {
+		match self {
+			GdsCost::Uniform => 1.0,
+			GdsCost::Size => size as f64,
+			GdsCost::Latency => LATENCY_BASE + size as f64 / LATENCY_BANDWIDTH,
+		}
+	}
+
+	/// Returns the value (F * C / S) of keeping an object of the supplied
+	/// size and frequency in the cache.
+	pub fn value(&self, size: Size, frequency: u64) -> f64 {
+		frequency as f64 * self.cost(size) / size as f64
+	}
+}
+
+impl FromStr for GdsCost {
+	type Err = Error;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match value {
+			"uniform" => Ok(GdsCost::Uniform),
+			"size" => Ok(GdsCost::Size),
+			"latency" => Ok(GdsCost::Latency),
+
+			_ => Err(Error::new(
+				ErrorKind::InvalidData,
+				"Invalid GreedyDual-Size cost function.",
+			)),
+		}
+	}
+}
+
+impl GdsObject {
+	fn new(access: &Access) -> Self {
+		GdsObject {
+			object: Object::new(access),
+			frequency: 1,
+
+			priority: Priority::new(0.0),
+			sequence: 0,
+		}
+	}
+
+	fn queue_key(&self) -> (Priority, u64, Key) {
+		(self.priority, self.sequence, self.object.key)
+	}
+}
+
+impl Priority {
+	fn new(value: f64) -> Self {
+		Priority(value.max(0.0).to_bits())
+	}
+
+	fn value(&self) -> f64 {
+		f64::from_bits(self.0)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		access::{Access, Command},
+		cache::{Cache, GdsCache, GdsCost},
+	};
+
+	fn access(timestamp: u64, key: u64, size: u32) -> Access {
+		Access {
+			timestamp,
+			command: Command::Get,
+			key,
+			size,
+			ttl: None,
+		}
+	}
+
+	#[test]
+	fn large_objects_are_evicted_first() {
+		let mut cache = GdsCache::new(100, GdsCost::Uniform, false);
+
+		cache.handle_self_populating(&access(1, 0, 10));
+		cache.handle_self_populating(&access(2, 1, 60));
+		cache.handle_self_populating(&access(3, 2, 10));
+		cache.handle_self_populating(&access(4, 3, 30));
+
+		assert!(cache.has(0));
+		assert!(!cache.has(1));
+		assert!(cache.has(2));
+		assert!(cache.has(3));
+	}
+
+	#[test]
+	fn frequent_objects_are_kept() {
+		let mut cache = GdsCache::new(30, GdsCost::Uniform, true);
+
+		for timestamp in 1..=5 {
+			cache.handle_self_populating(&access(timestamp, 0, 10));
+		}
+
+		for key in 1..=10 {
+			cache.handle_self_populating(&access(5 + key, key, 10));
+		}
+
+		assert!(cache.has(0));
+	}
+}
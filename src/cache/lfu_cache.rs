@@ -5,14 +5,19 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use std::io::{Error, Read, Write};
+
 use rustc_hash::FxHashMap;
 use dlv_list::{VecList, Index};
+use serde::{Serialize, Deserialize};
 
 use crate::{
 	cache::{Cache, Object},
 	access::{Access, Key},
+	checkpoint,
 };
 
+#[derive(Serialize, Deserialize)]
 pub struct LfuCache {
 	max_size: u64,
 	current_size: u64,
@@ -20,10 +25,12 @@ pub struct LfuCache {
 	count: f64,
 	hits: f64,
 
+	#[serde(skip)]
 	map: FxHashMap<Key, LfuObjectIndex>,
 	count_lists: VecList<CountList>,
 }
 
+#[derive(Serialize, Deserialize)]
 struct CountList {
 	count: u64,
 	list: VecList<LfuObject>,
@@ -35,8 +42,10 @@ struct LfuObjectIndex {
 	list_index: Option<Index<LfuObject>>,
 }
 
+#[derive(Serialize, Deserialize)]
 struct LfuObject {
 	object: Object,
+	#[serde(skip)]
 	index: LfuObjectIndex,
 }
 
@@ -193,6 +202,33 @@ impl Cache for LfuCache {
 		self.count *= ratio;
 		self.hits *= ratio;
 	}
+
+	fn save(&self, writer: &mut dyn Write) -> Result<(), Error> {
+		checkpoint::save(writer, self)
+	}
+
+	fn restore(&mut self, reader: &mut dyn Read) -> Result<(), Error> {
+		*self = checkpoint::restore(reader)?;
+
+		// the indices of the objects change when the lists are restored,
+		// so they are rebuilt rather than saved
+		for count_list_index in self.count_lists.indices().collect::<Vec<_>>() {
+			let count_list = self.count_lists.get_mut(count_list_index).unwrap();
+
+			for list_index in count_list.list.indices().collect::<Vec<_>>() {
+				let lfu_object = count_list.list.get_mut(list_index).unwrap();
+
+				lfu_object.index = LfuObjectIndex {
+					count_list_index: Some(count_list_index),
+					list_index: Some(list_index),
+				};
+
+				self.map.insert(lfu_object.object.key, lfu_object.index.clone());
+			}
+		}
+
+		Ok(())
+	}
 }
 
 impl CountList {
@@ -0,0 +1,512 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::io::{Error, Read, Write};
+
+use rustc_hash::FxHashMap;
+use dlv_list::{VecList, Index};
+use serde::{Serialize, Deserialize};
+
+use crate::{
+	cache::{Cache, Object},
+	access::{Access, Key},
+	checkpoint,
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct LirsCache {
+	max_size: u64,
+	hir_ratio: f64,
+
+	count: f64,
+	hits: f64,
+
+	map: FxHashMap<Key, LirsEntry>,
+
+	// the LIRS stack (S), ordered by recency
+	stack: VecList<Object>,
+
+	// the resident HIR objects (Q)
+	queue: VecList<Object>,
+
+	// the non-resident HIR objects which remain in the LIRS stack
+	non_resident: VecList<Object>,
+
+	lir_size: u64,
+	hir_size: u64,
+	non_resident_size: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum Status {
+	Lir,
+	ResidentHir,
+	NonResidentHir,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LirsEntry {
+	status: Status,
+
+	#[serde(skip)]
+	stack_index: Option<Index<Object>>,
+
+	// the index in the queue for resident HIR objects, or in the list
+	// of non-resident objects for non-resident HIR objects
+	#[serde(skip)]
+	queue_index: Option<Index<Object>>,
+}
+
+impl LirsCache {
+	pub fn new(size: u64, hir_ratio: f64) -> Self {
+		assert!(hir_ratio > 0.0);
+		assert!(hir_ratio < 1.0);
+
+		LirsCache {
+			max_size: size,
+			hir_ratio,
+
+			count: 0.0,
+			hits: 0.0,
+
+			map: FxHashMap::default(),
+
+			stack: VecList::new(),
+			queue: VecList::new(),
+			non_resident: VecList::new(),
+
+			lir_size: 0,
+			hir_size: 0,
+			non_resident_size: 0,
+		}
+	}
+}
+
+impl Cache for LirsCache {
+	fn size(&self) -> u64 {
+		self.max_size
+	}
+
+	fn miss_ratio(&self) -> f64 {
+		if self.count > 0.0 {
+			return 1.0 - self.hits / self.count;
+		}
+
+		0.0
+	}
+
+	fn increment_count(&mut self) {
+		self.count += 1.0
+	}
+
+	fn increment_hits(&mut self) {
+		self.hits += 1.0
+	}
+
+	fn clear_counters(&mut self) {
+		self.count = 0.0;
+		self.hits = 0.0;
+	}
+
+	fn process_get(&mut self, access: &Access) -> bool {
+		let Some(entry) = self.map.get(&access.key) else {
+			return false;
+		};
+
+		if entry.status == Status::NonResidentHir {
+			return false;
+		}
+
+		// the object keeps the size with which it was inserted, so the
+		// LIR and HIR sizes remain consistent
+		let object_size = entry.queue_index
+			.and_then(|index| self.queue.get(index))
+			.or_else(|| entry.stack_index.and_then(|index| self.stack.get(index)))
+			.map(|object| object.size)
+			.unwrap();
+
+		let access = &Access {
+			size: object_size,
+			..access.clone()
+		};
+
+		match entry.status {
+			Status::Lir => {
+				self.move_to_stack_top(access);
+				self.prune_stack();
+			},
+
+			Status::ResidentHir if entry.stack_index.is_some() => {
+				self.remove_from_queue(access.key);
+				self.move_to_stack_top(access);
+
+				self.set_status(access.key, Status::Lir);
+				self.lir_size += access.size as u64;
+
+				self.demote_lir_objects();
+			},
+
+			Status::ResidentHir => {
+				self.remove_from_queue(access.key);
+				self.move_to_stack_top(access);
+				self.push_to_queue(access);
+			},
+
+			Status::NonResidentHir => return false,
+		}
+
+		true
+	}
+
+	fn process_set(&mut self, access: &Access) {
+		if access.size as u64 > self.max_size || self.has(access.key) {
+			return;
+		}
+
+		self.reduce(self.max_size - access.size as u64);
+
+		let object_size = access.size as u64;
+
+		// the object is a non-resident HIR object in the LIRS stack, so
+		// its inter-reference recency is smaller than the oldest LIR object
+		if let Some(index) = self.remove_non_resident(access.key) {
+			self.stack.remove(index);
+			self.push_to_stack_top(access, Status::Lir);
+			self.lir_size += object_size;
+
+			self.demote_lir_objects();
+
+			return;
+		}
+
+		if self.lir_size + object_size <= self.lir_max_size() {
+			self.push_to_stack_top(access, Status::Lir);
+			self.lir_size += object_size;
+
+			return;
+		}
+
+		self.push_to_stack_top(access, Status::ResidentHir);
+		self.push_to_queue(access);
+	}
+
+	fn process_del(&mut self, key: Key) {
+		let Some(entry) = self.map.get(&key) else {
+			return;
+		};
+
+		match entry.status {
+			Status::Lir => {
+				let object = self.stack.remove(entry.stack_index.unwrap()).unwrap();
+				self.lir_size -= object.size as u64;
+			},
+
+			Status::ResidentHir => {
+				if let Some(index) = entry.stack_index {
+					self.stack.remove(index);
+				}
+
+				self.remove_from_queue(key);
+			},
+
+			Status::NonResidentHir => {
+				if let Some(index) = self.remove_non_resident(key) {
+					self.stack.remove(index);
+				}
+			},
+		}
+
+		self.map.remove(&key);
+		self.prune_stack();
+	}
+
+	fn process_has(&self, key: Key) -> bool {
+		self.map
+			.get(&key)
+			.is_some_and(|entry| entry.status != Status::NonResidentHir)
+	}
+
+	fn reduce(&mut self, target_size: u64) {
+		while self.lir_size + self.hir_size > target_size {
+			if self.queue.is_empty() {
+				self.demote_bottom_lir_object();
+			}
+
+			self.evict_hir_object();
+		}
+	}
+
+	fn resize(&mut self, size: u64) {
+		self.reduce(size);
+		self.max_size = size;
+
+		self.demote_lir_objects();
+		self.trim_non_resident();
+	}
+
+	fn rescale(&mut self, ratio: f64) {
+		self.count *= ratio;
+		self.hits *= ratio;
+	}
+
+	fn save(&self, writer: &mut dyn Write) -> Result<(), Error> {
+		checkpoint::save(writer, self)
+	}
+
+	fn restore(&mut self, reader: &mut dyn Read) -> Result<(), Error> {
+		*self = checkpoint::restore(reader)?;
+
+		// the indices of the objects change when the lists are restored,
+		// so they are rebuilt rather than saved with the entries
+		for index in self.stack.indices() {
+			let key = self.stack.get(index).unwrap().key;
+			self.map.get_mut(&key).unwrap().stack_index = Some(index);
+		}
+
+		for list in [&self.queue, &self.non_resident] {
+			for index in list.indices() {
+				let key = list.get(index).unwrap().key;
+				self.map.get_mut(&key).unwrap().queue_index = Some(index);
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl LirsCache {
+	fn lir_max_size(&self) -> u64 {
+		((1.0 - self.hir_ratio) * self.max_size as f64) as u64
+	}
+
+	fn set_status(&mut self, key: Key, status: Status) {
+		if let Some(entry) = self.map.get_mut(&key) {
+			entry.status = status;
+		}
+	}
+
+	fn push_to_stack_top(&mut self, access: &Access, status: Status) {
+		let index = self.stack.push_front(Object::new(access));
+
+		self.map.insert(access.key, LirsEntry {
+			status,
+
+			stack_index: Some(index),
+			queue_index: None,
+		});
+	}
+
+	fn move_to_stack_top(&mut self, access: &Access) {
+		let entry = self.map.get_mut(&access.key).unwrap();
+
+		if let Some(index) = entry.stack_index {
+			self.stack.remove(index);
+		}
+
+		entry.stack_index = Some(self.stack.push_front(Object::new(access)));
+	}
+
+	fn push_to_queue(&mut self, access: &Access) {
+		let entry = self.map.get_mut(&access.key).unwrap();
+
+		entry.queue_index = Some(self.queue.push_front(Object::new(access)));
+		self.hir_size += access.size as u64;
+	}
+
+	fn remove_from_queue(&mut self, key: Key) {
+		let entry = self.map.get_mut(&key).unwrap();
+
+		if let Some(object) = entry.queue_index.take().and_then(|index| self.queue.remove(index)) {
+			self.hir_size -= object.size as u64;
+		}
+	}
+
+	/// Removes the object from the list of non-resident HIR objects and
+	/// returns its index in the LIRS stack.
+	fn remove_non_resident(&mut self, key: Key) -> Option<Index<Object>> {
+		let entry = self.map.get_mut(&key)?;
+
+		if entry.status != Status::NonResidentHir {
+			return None;
+		}
+
+		if let Some(object) = entry.queue_index.take().and_then(|index| self.non_resident.remove(index)) {
+			self.non_resident_size -= object.size as u64;
+		}
+
+		let stack_index = entry.stack_index;
+		self.map.remove(&key);
+
+		stack_index
+	}
+
+	/// Demotes the LIR objects at the bottom of the LIRS stack until
+	/// the LIR objects fit in their portion of the cache.
+	fn demote_lir_objects(&mut self) {
+		while self.lir_size > self.lir_max_size() {
+			self.demote_bottom_lir_object();
+		}
+	}
+
+	fn demote_bottom_lir_object(&mut self) {
+		let Some(object) = self.stack.pop_back() else {
+			return;
+		};
+
+		let entry = self.map.get_mut(&object.key).unwrap();
+
+		entry.status = Status::ResidentHir;
+		entry.stack_index = None;
+		entry.queue_index = Some(self.queue.push_front(object.clone()));
+
+		self.lir_size -= object.size as u64;
+		self.hir_size += object.size as u64;
+
+		self.prune_stack();
+	}
+
+	/// Evicts the resident HIR object at the front of the queue. If it is
+	/// still in the LIRS stack, it remains there as a non-resident object.
+	fn evict_hir_object(&mut self) {
+		let Some(object) = self.queue.pop_back() else {
+			return;
+		};
+
+		self.hir_size -= object.size as u64;
+
+		let entry = self.map.get_mut(&object.key).unwrap();
+
+		if entry.stack_index.is_none() {
+			self.map.remove(&object.key);
+			return;
+		}
+
+		self.non_resident_size += object.size as u64;
+
+		entry.status = Status::NonResidentHir;
+		entry.queue_index = Some(self.non_resident.push_front(object));
+
+		self.trim_non_resident();
+	}
+
+	/// Removes HIR objects from the bottom of the LIRS stack so that
+	/// the bottom object is always a LIR object.
+	fn prune_stack(&mut self) {
+		while let Some(object) = self.stack.back() {
+			let key = object.key;
+			let entry = self.map.get_mut(&key).unwrap();
+
+			if entry.status == Status::Lir {
+				return;
+			}
+
+			self.stack.pop_back();
+			entry.stack_index = None;
+
+			if entry.status == Status::NonResidentHir {
+				if let Some(object) = entry.queue_index.take().and_then(|index| self.non_resident.remove(index)) {
+					self.non_resident_size -= object.size as u64;
+				}
+
+				self.map.remove(&key);
+			}
+		}
+	}
+
+	/// Bounds the metadata kept for non-resident HIR objects to the size
+	/// of the cache.
+	fn trim_non_resident(&mut self) {
+		while self.non_resident_size > self.max_size {
+			let Some(object) = self.non_resident.pop_back() else {
+				return;
+			};
+
+			self.non_resident_size -= object.size as u64;
+
+			if let Some(index) = self.map.remove(&object.key).and_then(|entry| entry.stack_index) {
+				self.stack.remove(index);
+			}
+		}
+
+		self.prune_stack();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		access::{Access, Command},
+		cache::{Cache, LirsCache, LruCache},
+	};
+
+	fn looping_trace(loop_length: u64, loops: u64) -> Vec<Access> {
+		(0..loop_length * loops)
+			.map(|index| Access {
+				timestamp: index + 1,
+				command: Command::Get,
+				key: index % loop_length,
+				size: 1,
+				ttl: None,
+			})
+			.collect()
+	}
+
+	fn miss_ratio(cache: &mut dyn Cache, trace: &[Access]) -> f64 {
+		for access in trace {
+			cache.handle_self_populating(access);
+		}
+
+		cache.miss_ratio()
+	}
+
+	#[test]
+	fn lirs_beats_lru_on_loop() {
+		let trace = looping_trace(120, 50);
+
+		let lru_miss_ratio = miss_ratio(&mut LruCache::new(100), &trace);
+		let lirs_miss_ratio = miss_ratio(&mut LirsCache::new(100, 0.01), &trace);
+
+		assert_eq!(lru_miss_ratio, 1.0);
+		assert!(lirs_miss_ratio < 0.3);
+	}
+
+	#[test]
+	fn lirs_beats_lru_on_loop_with_hot_set() {
+		let mut trace = Vec::<Access>::new();
+
+		for index in 0..10_000u64 {
+			let key = match index % 2 {
+				0 => index / 2 % 200,
+				_ => 1_000 + index % 10,
+			};
+
+			trace.push(Access {
+				timestamp: index + 1,
+				command: Command::Get,
+				key,
+				size: 1,
+				ttl: None,
+			});
+		}
+
+		let lru_miss_ratio = miss_ratio(&mut LruCache::new(150), &trace);
+		let lirs_miss_ratio = miss_ratio(&mut LirsCache::new(150, 0.01), &trace);
+
+		assert!(lirs_miss_ratio < lru_miss_ratio - 0.2);
+	}
+
+	#[test]
+	fn size_is_bounded() {
+		let mut cache = LirsCache::new(100, 0.1);
+
+		for access in looping_trace(300, 3) {
+			cache.handle_self_populating(&access);
+			assert!(cache.lir_size + cache.hir_size <= 100);
+			assert!(cache.lir_size <= 90);
+		}
+	}
+}
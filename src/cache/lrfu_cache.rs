@@ -5,15 +5,19 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use std::io::{Error, Read, Write};
 use std::collections::BTreeSet;
 use std::cmp::{Ord, Ordering};
 use rustc_hash::FxHashMap;
+use serde::{Serialize, Deserialize};
 
 use crate::{
 	cache::{Cache, Object},
 	access::{Access, Timestamp, Key},
+	checkpoint,
 };
 
+#[derive(Serialize, Deserialize)]
 pub struct LrfuCache {
 	max_size: u64,
 	current_size: u64,
@@ -30,7 +34,7 @@ pub struct LrfuCache {
 	intrinsic_timestamp: Timestamp,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 struct LrfuObject {
 	object: Object,
 
@@ -173,6 +177,15 @@ impl Cache for LrfuCache {
 		self.count *= ratio;
 		self.hits *= ratio;
 	}
+
+	fn save(&self, writer: &mut dyn Write) -> Result<(), Error> {
+		checkpoint::save(writer, self)
+	}
+
+	fn restore(&mut self, reader: &mut dyn Read) -> Result<(), Error> {
+		*self = checkpoint::restore(reader)?;
+		Ok(())
+	}
 }
 
 impl LrfuObject {
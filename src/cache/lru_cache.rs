@@ -5,14 +5,19 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use std::io::{Error, Read, Write};
+
 use rustc_hash::FxHashMap;
 use dlv_list::{VecList, Index};
+use serde::{Serialize, Deserialize};
 
 use crate::{
 	cache::{Cache, Object},
 	access::{Access, Key},
+	checkpoint,
 };
 
+#[derive(Serialize, Deserialize)]
 pub struct LruCache {
 	max_size: u64,
 	current_size: u64,
@@ -20,6 +25,7 @@ pub struct LruCache {
 	count: f64,
 	hits: f64,
 
+	#[serde(skip)]
 	map: FxHashMap<Key, Index<Object>>,
 	stack: VecList<Object>,
 }
@@ -125,4 +131,20 @@ impl Cache for LruCache {
 		self.count *= ratio;
 		self.hits *= ratio;
 	}
+
+	fn save(&self, writer: &mut dyn Write) -> Result<(), Error> {
+		checkpoint::save(writer, self)
+	}
+
+	fn restore(&mut self, reader: &mut dyn Read) -> Result<(), Error> {
+		*self = checkpoint::restore(reader)?;
+
+		// the indices of the objects change when the stack is restored,
+		// so the map is rebuilt rather than saved
+		for index in self.stack.indices() {
+			self.map.insert(self.stack.get(index).unwrap().key, index);
+		}
+
+		Ok(())
+	}
 }
@@ -0,0 +1,362 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::io::{Error, Read, Write};
+use std::collections::BTreeSet;
+
+use rustc_hash::FxHashMap;
+use serde::{Serialize, Deserialize};
+
+use crate::{
+	cache::{Cache, Object},
+	access::{Access, Key, Timestamp},
+	checkpoint,
+};
+
+// the smallest number of evicted objects whose history is retained
+const MIN_RETAINED_HISTORY: usize = 1024;
+
+/// An LRU-K cache, which evicts the object whose K-th most recent access
+/// is the oldest. Objects with fewer than K accesses are evicted first (in
+/// LRU order). Accesses within the correlated reference period of the last
+/// access to an object do not count towards its history, and objects which
+/// were accessed within the period are not evicted unless every object
+/// was. The history of the most recently accessed evicted objects is
+/// retained, up to the larger of the number of resident objects and
+/// `MIN_RETAINED_HISTORY`, so the memory of the cache is bounded by its
+/// size rather than by the number of keys in the trace.
+#[derive(Serialize, Deserialize)]
+pub struct LruKCache {
+	max_size: u64,
+	current_size: u64,
+
+	k: usize,
+	correlated_period: Timestamp,
+	timestamp: Timestamp,
+
+	count: f64,
+	hits: f64,
+
+	map: FxHashMap<Key, LruKObject>,
+
+	// the resident objects, ordered by their K-th most recent access, then
+	// by their most recent access
+	queue: BTreeSet<(Timestamp, Timestamp, Key)>,
+
+	// the evicted objects whose history is retained, ordered by their most
+	// recent access
+	retained: BTreeSet<(Timestamp, Key)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LruKObject {
+	object: Object,
+
+	// the timestamps of the K most recent uncorrelated accesses, most
+	// recent first
+	history: Vec<Timestamp>,
+	last: Timestamp,
+
+	resident: bool,
+}
+
+impl LruKCache {
+	pub fn new(size: u64, k: usize, correlated_period: Timestamp) -> Self {
+		assert!(k > 0);
+
+		LruKCache {
+			max_size: size,
+			current_size: 0,
+
+			k,
+			correlated_period,
+			timestamp: 0,
+
+			count: 0.0,
+			hits: 0.0,
+
+			map: FxHashMap::default(),
+			queue: BTreeSet::new(),
+			retained: BTreeSet::new(),
+		}
+	}
+}
+
+impl Cache for LruKCache {
+	fn size(&self) -> u64 {
+		self.max_size
+	}
+
+	fn miss_ratio(&self) -> f64 {
+		if self.count > 0.0 {
+			return 1.0 - self.hits / self.count;
+		}
+
+		0.0
+	}
+
+	fn increment_count(&mut self) {
+		self.count += 1.0
+	}
+
+	fn increment_hits(&mut self) {
+		self.hits += 1.0
+	}
+
+	fn clear_counters(&mut self) {
+		self.count = 0.0;
+		self.hits = 0.0;
+	}
+
+	fn process_get(&mut self, access: &Access) -> bool {
+		self.timestamp = access.timestamp;
+
+		let Some(lru_k_object) = self.map.get_mut(&access.key) else {
+			return false;
+		};
+
+		if !lru_k_object.resident {
+			return false;
+		}
+
+		self.queue.remove(&lru_k_object.queue_key(self.k));
+
+		if access.timestamp.saturating_sub(lru_k_object.last) > self.correlated_period {
+			// the correlated accesses since the last uncorrelated access
+			// are collapsed into a single access
+			let correlated_duration = lru_k_object.last - lru_k_object.history[0];
+
+			for timestamp in lru_k_object.history.iter_mut() {
+				*timestamp += correlated_duration;
+			}
+
+			lru_k_object.push_history(access.timestamp, self.k);
+		}
+
+		lru_k_object.last = access.timestamp;
+		self.queue.insert(lru_k_object.queue_key(self.k));
+
+		true
+	}
+
+	fn process_set(&mut self, access: &Access) {
+		if access.size as u64 > self.max_size || self.has(access.key) {
+			return;
+		}
+
+		self.timestamp = access.timestamp;
+		self.reduce(self.max_size - access.size as u64);
+
+		let lru_k_object = match self.map.remove(&access.key) {
+			Some(mut lru_k_object) => {
+				self.retained.remove(&(lru_k_object.last, access.key));
+
+				lru_k_object.resident = true;
+				lru_k_object.object = Object::new(access);
+				lru_k_object.last = access.timestamp;
+				lru_k_object.push_history(access.timestamp, self.k);
+
+				lru_k_object
+			},
+
+			None => LruKObject::new(access),
+		};
+
+		self.queue.insert(lru_k_object.queue_key(self.k));
+		self.map.insert(access.key, lru_k_object);
+
+		self.current_size += access.size as u64;
+	}
+
+	fn process_del(&mut self, key: Key) {
+		let Some(lru_k_object) = self.map.remove(&key) else {
+			return;
+		};
+
+		if lru_k_object.resident {
+			self.queue.remove(&lru_k_object.queue_key(self.k));
+			self.current_size -= lru_k_object.object.size as u64;
+		} else {
+			self.retained.remove(&(lru_k_object.last, key));
+		}
+	}
+
+	fn process_has(&self, key: Key) -> bool {
+		self.map
+			.get(&key)
+			.is_some_and(|lru_k_object| lru_k_object.resident)
+	}
+
+	fn reduce(&mut self, target_size: u64) {
+		while self.current_size > target_size {
+			let Some(key) = self.eviction_candidate() else {
+				return;
+			};
+
+			let lru_k_object = self.map.get_mut(&key).unwrap();
+
+			self.queue.remove(&lru_k_object.queue_key(self.k));
+			self.current_size -= lru_k_object.object.size as u64;
+
+			lru_k_object.resident = false;
+			self.retained.insert((lru_k_object.last, key));
+		}
+
+		self.trim_retained();
+	}
+
+	fn resize(&mut self, size: u64) {
+		self.reduce(size);
+		self.max_size = size;
+	}
+
+	fn rescale(&mut self, ratio: f64) {
+		self.count *= ratio;
+		self.hits *= ratio;
+	}
+
+	fn save(&self, writer: &mut dyn Write) -> Result<(), Error> {
+		checkpoint::save(writer, self)
+	}
+
+	fn restore(&mut self, reader: &mut dyn Read) -> Result<(), Error> {
+		*self = checkpoint::restore(reader)?;
+		Ok(())
+	}
+}
+
+impl LruKCache {
+	/// Drops the history of the least recently accessed evicted objects
+	/// until the retained history fits.
+	fn trim_retained(&mut self) {
+		let num_resident = self.map.len() - self.retained.len();
+		let max_retained = num_resident.max(MIN_RETAINED_HISTORY);
+
+		while self.retained.len() > max_retained {
+			if let Some((_, key)) = self.retained.pop_first() {
+				self.map.remove(&key);
+			}
+		}
+	}
+
+	/// Returns the key of the object with the oldest K-th most recent
+	/// access which was not accessed within the correlated reference
+	/// period, or of the object with the oldest K-th most recent access if
+	/// every object was.
+	fn eviction_candidate(&self) -> Option<Key> {
+		self.queue
+			.iter()
+			.find(|(_, last, _)| self.timestamp.saturating_sub(*last) > self.correlated_period)
+			.or(self.queue.first())
+			.map(|(_, _, key)| *key)
+	}
+}
+
+impl LruKObject {
+	fn new(access: &Access) -> Self {
+		LruKObject {
+			object: Object::new(access),
+
+			history: vec![access.timestamp],
+			last: access.timestamp,
+
+			resident: true,
+		}
+	}
+
+	fn push_history(&mut self, timestamp: Timestamp, k: usize) {
+		self.history.insert(0, timestamp);
+		self.history.truncate(k);
+	}
+
+	/// Returns the queue key of the object. Objects with fewer than K
+	/// accesses have an infinite backward K-distance, and so are ordered
+	/// before all others.
+	fn queue_key(&self, k: usize) -> (Timestamp, Timestamp, Key) {
+		let kth_timestamp = self.history
+			.get(k - 1)
+			.copied()
+			.unwrap_or(0);
+
+		(kth_timestamp, self.last, self.object.key)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		access::{Access, Command},
+		cache::{Cache, LruKCache, LruCache, lru_k_cache::MIN_RETAINED_HISTORY},
+	};
+
+	fn access(timestamp: u64, key: u64) -> Access {
+		Access {
+			timestamp,
+			command: Command::Get,
+			key,
+			size: 1,
+			ttl: None,
+		}
+	}
+
+	fn miss_ratio(cache: &mut dyn Cache, trace: &[Access]) -> f64 {
+		for access in trace {
+			cache.handle_self_populating(access);
+		}
+
+		cache.miss_ratio()
+	}
+
+	#[test]
+	fn hot_set_survives_scan() {
+		let trace = (0..20_000u64)
+			.map(|index| {
+				let key = match index % 4 {
+					0 => index / 4 % 30,
+					_ => 1_000 + index,
+				};
+
+				access(index + 1, key)
+			})
+			.collect::<Vec<Access>>();
+
+		let lru_miss_ratio = miss_ratio(&mut LruCache::new(60), &trace);
+		let lru_k_miss_ratio = miss_ratio(&mut LruKCache::new(60, 2, 0), &trace);
+
+		assert_eq!(lru_miss_ratio, 1.0);
+		assert!(lru_k_miss_ratio < 0.8);
+	}
+
+	#[test]
+	fn lru_1_is_lru() {
+		let trace = (0..5_000u64)
+			.map(|index| access(index + 1, index * 7 % 13 + index % 5 * 11))
+			.collect::<Vec<Access>>();
+
+		let lru_miss_ratio = miss_ratio(&mut LruCache::new(20), &trace);
+		let lru_k_miss_ratio = miss_ratio(&mut LruKCache::new(20, 1, 0), &trace);
+
+		assert_eq!(lru_miss_ratio, lru_k_miss_ratio);
+	}
+
+	#[test]
+	fn retained_history_is_bounded() {
+		let mut cache = LruKCache::new(2, 2, 0);
+
+		for index in 0..5_000u64 {
+			cache.handle_self_populating(&access(index + 1, index));
+		}
+
+		assert_eq!(cache.retained.len(), MIN_RETAINED_HISTORY);
+		assert_eq!(cache.map.len(), MIN_RETAINED_HISTORY + 2);
+
+		// the most recently evicted objects keep their history, and the
+		// oldest do not
+		assert!(cache.map.contains_key(&4_997));
+		assert!(!cache.map.contains_key(&0));
+	}
+}
@@ -0,0 +1,78 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::{
+	fmt::{self, Debug, Formatter},
+	sync::{Arc, LazyLock, RwLock},
+};
+
+use rustc_hash::FxHashMap;
+
+use crate::cache::Cache;
+
+type CacheFactory = dyn Fn(u64) -> Box<dyn Cache> + Send + Sync;
+
+static PLUGINS: LazyLock<RwLock<FxHashMap<String, Arc<CacheFactory>>>> =
+	LazyLock::new(|| RwLock::new(FxHashMap::default()));
+
+/// A user-defined cache policy, which creates caches of a given size
+/// using the factory it was registered with.
+#[derive(Clone)]
+pub struct CachePluginPolicy {
+	name: String,
+	factory: Arc<CacheFactory>,
+}
+
+/// Registers a cache factory under the supplied policy name, replacing any
+/// factory which was previously registered under it. Once registered, the
+/// policy can be parsed as a `CachePolicy` from its name, unless the name is
+/// that of a built-in policy without parameters (e.g., lru).
+pub fn register_plugin<F>(name: &str, factory: F)
+where
+	F: Fn(u64) -> Box<dyn Cache> + Send + Sync + 'static,
+{
+	PLUGINS
+		.write()
+		.unwrap()
+		.insert(name.to_string(), Arc::new(factory));
+}
+
+/// Returns the plugin policy registered under the supplied name, if any.
+pub fn find_plugin(name: &str) -> Option<CachePluginPolicy> {
+	let factory = PLUGINS
+		.read()
+		.unwrap()
+		.get(name)
+		.cloned()?;
+
+	Some(CachePluginPolicy {
+		name: name.to_string(),
+		factory,
+	})
+}
+
+impl CachePluginPolicy {
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	pub fn new_cache(&self, size: u64) -> Box<dyn Cache> {
+		(self.factory)(size)
+	}
+}
+
+impl Debug for CachePluginPolicy {
+	fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+		formatter.debug_tuple("CachePluginPolicy").field(&self.name).finish()
+	}
+}
+
+impl PartialEq for CachePluginPolicy {
+	fn eq(&self, other: &Self) -> bool {
+		self.name == other.name
+	}
+}
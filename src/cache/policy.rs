@@ -16,22 +16,48 @@ use serde::{
 	de::{self, Deserializer, Visitor},
 };
 
-use crate::cache::{
-	Cache,
-	LfuCache,
-	FifoCache,
-	TwoQCache,
-	LruCache,
-	LrfuCache,
+use crate::{
+	access::Timestamp,
+	policy_config,
+	cache::{
+		Cache,
+		LfuCache,
+		FifoCache,
+		TwoQCache,
+		LruCache,
+		LrfuCache,
+		SieveCache,
+		S3FifoCache,
+		ArcCache,
+		LirsCache,
+		GdsCache,
+		GdsCost,
+		WTinyLfuCache,
+		LruKCache,
+		SlruCache,
+		plugin::{self, CachePluginPolicy},
+	},
 };
 
-#[derive(Debug, Clone)]
+const DEFAULT_W_TINY_LFU_WINDOW_RATIO: f64 = 0.01;
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum CachePolicy {
 	Lfu,
 	Fifo,
 	TwoQ(f64, f64),
 	Lrfu(f64, f64),
 	Lru,
+	Sieve,
+	S3Fifo(f64, f64),
+	Arc,
+	Lirs(f64),
+	Gds(GdsCost),
+	Gdsf(GdsCost),
+	WTinyLfu(f64),
+	LruK(usize, Timestamp),
+	Slru(Vec<f64>),
+	Plugin(CachePluginPolicy),
 }
 
 impl CachePolicy {
@@ -42,6 +68,16 @@ impl CachePolicy {
 			CachePolicy::TwoQ(kin, kout) => Box::new(TwoQCache::new(size, *kin, *kout)),
 			CachePolicy::Lrfu(p, lambda) => Box::new(LrfuCache::new(size, *p, *lambda)),
 			CachePolicy::Lru => Box::new(LruCache::new(size)),
+			CachePolicy::Sieve => Box::new(SieveCache::new(size)),
+			CachePolicy::S3Fifo(small_ratio, ghost_ratio) => Box::new(S3FifoCache::new(size, *small_ratio, *ghost_ratio)),
+			CachePolicy::Arc => Box::new(ArcCache::new(size)),
+			CachePolicy::Lirs(hir_ratio) => Box::new(LirsCache::new(size, *hir_ratio)),
+			CachePolicy::Gds(cost) => Box::new(GdsCache::new(size, *cost, false)),
+			CachePolicy::Gdsf(cost) => Box::new(GdsCache::new(size, *cost, true)),
+			CachePolicy::WTinyLfu(window_ratio) => Box::new(WTinyLfuCache::new(size, *window_ratio)),
+			CachePolicy::LruK(k, correlated_period) => Box::new(LruKCache::new(size, *k, *correlated_period)),
+			CachePolicy::Slru(ratios) => Box::new(SlruCache::new(size, ratios)),
+			CachePolicy::Plugin(plugin) => plugin.new_cache(size),
 		}
 	}
 }
@@ -54,14 +90,16 @@ impl FromStr for CachePolicy {
 			"lfu" => Ok(CachePolicy::Lfu),
 			"fifo" => Ok(CachePolicy::Fifo),
 			"lru" => Ok(CachePolicy::Lru),
-
-			value if value.starts_with("2q") => parse_two_q_config(value),
-			value if value.starts_with("lrfu") => parse_lrfu_config(value),
-
-			_ => Err(Error::new(
-				ErrorKind::InvalidData,
-				"Invalid cache policy.",
-			)),
+			"sieve" => Ok(CachePolicy::Sieve),
+			"arc" => Ok(CachePolicy::Arc),
+
+			// plugins are found before the configurable policies, so that a
+			// plugin whose name starts with the name of a built-in policy
+			// (e.g., lru2) can be selected
+			value => match plugin::find_plugin(value) {
+				Some(plugin) => Ok(CachePolicy::Plugin(plugin)),
+				None => parse_config(value),
+			},
 		}
 	}
 }
@@ -93,6 +131,33 @@ impl<'a> Visitor<'a> for CachePolicyVisitor {
 	}
 }
 
+/// Parses the config of a built-in policy which takes parameters.
+fn parse_config(value: &str) -> Result<CachePolicy, Error> {
+	match value {
+		value if value.starts_with("2q") => parse_two_q_config(value),
+		value if value.starts_with("lrfu") => parse_lrfu_config(value),
+		value if value.starts_with("s3fifo") => policy_config::parse_s3_fifo_config(value)
+			.map(|(small_ratio, ghost_ratio)| CachePolicy::S3Fifo(small_ratio, ghost_ratio)),
+		value if value.starts_with("lirs") => policy_config::parse_lirs_config(value)
+			.map(CachePolicy::Lirs),
+		value if value.starts_with("gds") => policy_config::parse_gds_config(value)
+			.map(|(frequency, cost)| match frequency {
+				true => CachePolicy::Gdsf(cost),
+				false => CachePolicy::Gds(cost),
+			}),
+		value if value.starts_with("wtinylfu") => parse_w_tiny_lfu_config(value),
+		value if value.starts_with("lruk") => policy_config::parse_lru_k_config(value)
+			.map(|(k, correlated_period)| CachePolicy::LruK(k, correlated_period)),
+		value if value.starts_with("slru") => policy_config::parse_slru_config(value)
+			.map(CachePolicy::Slru),
+
+		_ => Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid cache policy.",
+		)),
+	}
+}
+
 fn parse_two_q_config(value: &str) -> Result<CachePolicy, Error> {
 	let replaced = value.replace("2q-", "");
 
@@ -154,3 +219,25 @@ fn parse_lrfu_config(value: &str) -> Result<CachePolicy, Error> {
 
 	Ok(CachePolicy::Lrfu(p, lambda))
 }
+
+fn parse_w_tiny_lfu_config(value: &str) -> Result<CachePolicy, Error> {
+	let window_ratio = match value {
+		"wtinylfu" => Ok(DEFAULT_W_TINY_LFU_WINDOW_RATIO),
+		value => value
+			.strip_prefix("wtinylfu-")
+			.and_then(|value| value.parse::<f64>().ok())
+			.ok_or(Error::new(
+				ErrorKind::InvalidData,
+				"Invalid W-TinyLFU policy config window ratio value."
+			)),
+	}?;
+
+	if window_ratio <= 0.0 || window_ratio >= 1.0 {
+		return Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid W-TinyLFU policy config window ratio."
+		));
+	}
+
+	Ok(CachePolicy::WTinyLfu(window_ratio))
+}
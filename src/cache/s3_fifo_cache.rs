@@ -0,0 +1,324 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::io::{Error, Read, Write};
+
+use rustc_hash::FxHashMap;
+use dlv_list::{VecList, Index};
+use serde::{Serialize, Deserialize};
+
+use crate::{
+	cache::{Cache, Object},
+	access::{Access, Key},
+	checkpoint,
+};
+
+const MAX_FREQUENCY: u8 = 3;
+
+#[derive(Serialize, Deserialize)]
+pub struct S3FifoCache {
+	max_size: u64,
+
+	small_ratio: f64,
+	ghost_ratio: f64,
+
+	count: f64,
+	hits: f64,
+
+	#[serde(skip)]
+	map: FxHashMap<Key, QueueIndex>,
+
+	small: Queue,
+	main: Queue,
+	ghost: Queue,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Queue {
+	queue: VecList<S3FifoObject>,
+	size: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct S3FifoObject {
+	object: Object,
+	frequency: u8,
+}
+
+#[derive(Clone, Copy)]
+enum QueueIndex {
+	Small(Index<S3FifoObject>),
+	Main(Index<S3FifoObject>),
+	Ghost(Index<S3FifoObject>),
+}
+
+impl S3FifoCache {
+	pub fn new(size: u64, small_ratio: f64, ghost_ratio: f64) -> Self {
+		assert!(small_ratio > 0.0);
+		assert!(small_ratio < 1.0);
+		assert!(ghost_ratio >= 0.0);
+
+		S3FifoCache {
+			max_size: size,
+
+			small_ratio,
+			ghost_ratio,
+
+			count: 0.0,
+			hits: 0.0,
+
+			map: FxHashMap::default(),
+
+			small: Queue::default(),
+			main: Queue::default(),
+			ghost: Queue::default(),
+		}
+	}
+}
+
+impl Cache for S3FifoCache {
+	fn size(&self) -> u64 {
+		self.max_size
+	}
+
+	fn miss_ratio(&self) -> f64 {
+		if self.count > 0.0 {
+			return 1.0 - self.hits / self.count;
+		}
+
+		0.0
+	}
+
+	fn increment_count(&mut self) {
+		self.count += 1.0
+	}
+
+	fn increment_hits(&mut self) {
+		self.hits += 1.0
+	}
+
+	fn clear_counters(&mut self) {
+		self.count = 0.0;
+		self.hits = 0.0;
+	}
+
+	fn process_get(&mut self, access: &Access) -> bool {
+		let s3_fifo_object = match self.map.get(&access.key) {
+			Some(QueueIndex::Small(index)) => self.small.get_mut(*index),
+			Some(QueueIndex::Main(index)) => self.main.get_mut(*index),
+			_ => None,
+		};
+
+		let Some(s3_fifo_object) = s3_fifo_object else {
+			return false;
+		};
+
+		if s3_fifo_object.frequency < MAX_FREQUENCY {
+			s3_fifo_object.frequency += 1;
+		}
+
+		true
+	}
+
+	fn process_set(&mut self, access: &Access) {
+		if access.size as u64 > self.max_size || self.has(access.key) {
+			return;
+		}
+
+		self.reduce(self.max_size - access.size as u64);
+
+		let s3_fifo_object = S3FifoObject::new(access);
+
+		let queue_index = match self.map.get(&access.key) {
+			Some(QueueIndex::Ghost(index)) => {
+				self.ghost.remove(*index);
+				QueueIndex::Main(self.main.push_front(s3_fifo_object))
+			},
+
+			_ => QueueIndex::Small(self.small.push_front(s3_fifo_object)),
+		};
+
+		self.map.insert(access.key, queue_index);
+	}
+
+	fn process_del(&mut self, key: Key) {
+		let Some(queue_index) = self.map.remove(&key) else {
+			return;
+		};
+
+		match queue_index {
+			QueueIndex::Small(index) => self.small.remove(index),
+			QueueIndex::Main(index) => self.main.remove(index),
+			QueueIndex::Ghost(index) => self.ghost.remove(index),
+		};
+	}
+
+	fn process_has(&self, key: Key) -> bool {
+		matches!(
+			self.map.get(&key),
+			Some(QueueIndex::Small(_)) | Some(QueueIndex::Main(_)),
+		)
+	}
+
+	fn reduce(&mut self, target_size: u64) {
+		while self.current_size() > target_size {
+			// the small queue may be empty while still at its maximum size
+			// (e.g., if the cache is too small for the small queue to hold
+			// any bytes), in which case the main queue must be evicted from
+			if !self.small.is_empty() && (self.small.size >= self.small_max_size() || self.main.is_empty()) {
+				self.evict_small();
+			} else {
+				self.evict_main();
+			}
+		}
+
+		while self.ghost.size > self.ghost_max_size() {
+			self.evict_ghost();
+		}
+	}
+
+	fn resize(&mut self, size: u64) {
+		self.reduce(size);
+		self.max_size = size;
+	}
+
+	fn rescale(&mut self, ratio: f64) {
+		self.count *= ratio;
+		self.hits *= ratio;
+	}
+
+	fn save(&self, writer: &mut dyn Write) -> Result<(), Error> {
+		checkpoint::save(writer, self)
+	}
+
+	fn restore(&mut self, reader: &mut dyn Read) -> Result<(), Error> {
+		*self = checkpoint::restore(reader)?;
+
+		// the indices of the objects change when the lists are restored,
+		// so the map is rebuilt rather than saved
+		for index in self.small.queue.indices() {
+			self.map.insert(self.small.queue.get(index).unwrap().object.key, QueueIndex::Small(index));
+		}
+
+		for index in self.main.queue.indices() {
+			self.map.insert(self.main.queue.get(index).unwrap().object.key, QueueIndex::Main(index));
+		}
+
+		for index in self.ghost.queue.indices() {
+			self.map.insert(self.ghost.queue.get(index).unwrap().object.key, QueueIndex::Ghost(index));
+		}
+
+		Ok(())
+	}
+}
+
+impl S3FifoCache {
+	fn current_size(&self) -> u64 {
+		self.small.size + self.main.size
+	}
+
+	fn small_max_size(&self) -> u64 {
+		(self.small_ratio * self.max_size as f64) as u64
+	}
+
+	fn ghost_max_size(&self) -> u64 {
+		(self.ghost_ratio * self.max_size as f64) as u64
+	}
+
+	/// Evicts the object at the tail of the small queue into the ghost
+	/// queue, unless it was accessed more than once, in which case it is
+	/// moved to the main queue instead.
+	fn evict_small(&mut self) {
+		let Some(mut s3_fifo_object) = self.small.pop_back() else {
+			return;
+		};
+
+		let key = s3_fifo_object.object.key;
+
+		if s3_fifo_object.frequency > 1 {
+			s3_fifo_object.frequency = 0;
+
+			let index = self.main.push_front(s3_fifo_object);
+			self.map.insert(key, QueueIndex::Main(index));
+
+			return;
+		}
+
+		let index = self.ghost.push_front(s3_fifo_object);
+		self.map.insert(key, QueueIndex::Ghost(index));
+	}
+
+	/// Evicts the object at the tail of the main queue, reinserting it
+	/// at the head if it has been accessed since it was last checked.
+	fn evict_main(&mut self) {
+		while let Some(mut s3_fifo_object) = self.main.pop_back() {
+			let key = s3_fifo_object.object.key;
+
+			if s3_fifo_object.frequency > 0 {
+				s3_fifo_object.frequency -= 1;
+
+				let index = self.main.push_front(s3_fifo_object);
+				self.map.insert(key, QueueIndex::Main(index));
+
+				continue;
+			}
+
+			self.map.remove(&key);
+			return;
+		}
+	}
+
+	fn evict_ghost(&mut self) {
+		if let Some(s3_fifo_object) = self.ghost.pop_back() {
+			self.map.remove(&s3_fifo_object.object.key);
+		}
+	}
+}
+
+impl Queue {
+	fn is_empty(&self) -> bool {
+		self.queue.is_empty()
+	}
+
+	fn get_mut(&mut self, index: Index<S3FifoObject>) -> Option<&mut S3FifoObject> {
+		self.queue.get_mut(index)
+	}
+
+	fn remove(&mut self, index: Index<S3FifoObject>) -> Option<S3FifoObject> {
+		let s3_fifo_object = self.queue.remove(index);
+
+		if let Some(s3_fifo_object) = &s3_fifo_object {
+			self.size -= s3_fifo_object.object.size as u64;
+		}
+
+		s3_fifo_object
+	}
+
+	fn push_front(&mut self, s3_fifo_object: S3FifoObject) -> Index<S3FifoObject> {
+		self.size += s3_fifo_object.object.size as u64;
+		self.queue.push_front(s3_fifo_object)
+	}
+
+	fn pop_back(&mut self) -> Option<S3FifoObject> {
+		let s3_fifo_object = self.queue.pop_back();
+
+		if let Some(s3_fifo_object) = &s3_fifo_object {
+			self.size -= s3_fifo_object.object.size as u64;
+		}
+
+		s3_fifo_object
+	}
+}
+
+impl S3FifoObject {
+	fn new(access: &Access) -> Self {
+		S3FifoObject {
+			object: Object::new(access),
+			frequency: 0,
+		}
+	}
+}
@@ -0,0 +1,208 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::io::{Error, Read, Write};
+
+use rustc_hash::FxHashMap;
+use dlv_list::{VecList, Index};
+use serde::{Serialize, Deserialize};
+
+use crate::{
+	cache::{Cache, Object},
+	access::{Access, Key},
+	checkpoint,
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct SieveCache {
+	max_size: u64,
+	current_size: u64,
+
+	count: f64,
+	hits: f64,
+
+	#[serde(skip)]
+	map: FxHashMap<Key, Index<SieveObject>>,
+	queue: VecList<SieveObject>,
+
+	#[serde(skip)]
+	hand: Option<Index<SieveObject>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SieveObject {
+	object: Object,
+	visited: bool,
+}
+
+impl SieveCache {
+	pub fn new(size: u64) -> Self {
+		SieveCache {
+			max_size: size,
+			current_size: 0,
+
+			count: 0.0,
+			hits: 0.0,
+
+			map: FxHashMap::default(),
+			queue: VecList::new(),
+
+			hand: None,
+		}
+	}
+
+	/// Moves the hand from the tail towards the head of the queue,
+	/// clearing visited bits, until an unvisited object is found
+	/// and evicted.
+	fn evict(&mut self) {
+		let mut index = self.hand.or(self.queue.back_index());
+
+		while let Some(current_index) = index {
+			let sieve_object = self.queue.get_mut(current_index).unwrap();
+
+			if sieve_object.visited {
+				sieve_object.visited = false;
+
+				index = self.queue
+					.get_previous_index(current_index)
+					.or(self.queue.back_index());
+
+				continue;
+			}
+
+			self.hand = self.queue.get_previous_index(current_index);
+
+			let sieve_object = self.queue.remove(current_index).unwrap();
+
+			self.map.remove(&sieve_object.object.key);
+			self.current_size -= sieve_object.object.size as u64;
+
+			return;
+		}
+	}
+}
+
+impl Cache for SieveCache {
+	fn size(&self) -> u64 {
+		self.max_size
+	}
+
+	fn miss_ratio(&self) -> f64 {
+		if self.count > 0.0 {
+			return 1.0 - self.hits / self.count;
+		}
+
+		0.0
+	}
+
+	fn increment_count(&mut self) {
+		self.count += 1.0
+	}
+
+	fn increment_hits(&mut self) {
+		self.hits += 1.0
+	}
+
+	fn clear_counters(&mut self) {
+		self.count = 0.0;
+		self.hits = 0.0;
+	}
+
+	fn process_get(&mut self, access: &Access) -> bool {
+		let Some(index) = self.map.get(&access.key) else {
+			return false;
+		};
+
+		let Some(sieve_object) = self.queue.get_mut(*index) else {
+			return false;
+		};
+
+		sieve_object.visited = true;
+
+		true
+	}
+
+	fn process_set(&mut self, access: &Access) {
+		if access.size as u64 > self.max_size || self.has(access.key) {
+			return;
+		}
+
+		self.reduce(self.max_size - access.size as u64);
+
+		let sieve_object = SieveObject::new(access);
+		let index = self.queue.push_front(sieve_object);
+
+		self.map.insert(access.key, index);
+		self.current_size += access.size as u64;
+	}
+
+	fn process_del(&mut self, key: Key) {
+		let Some(index) = self.map.remove(&key) else {
+			return;
+		};
+
+		if self.hand == Some(index) {
+			self.hand = self.queue.get_previous_index(index);
+		}
+
+		let sieve_object = self.queue.remove(index).unwrap();
+		self.current_size -= sieve_object.object.size as u64;
+	}
+
+	fn process_has(&self, key: Key) -> bool {
+		self.map.contains_key(&key)
+	}
+
+	fn reduce(&mut self, target_size: u64) {
+		while self.current_size > target_size {
+			self.evict();
+		}
+	}
+
+	fn resize(&mut self, size: u64) {
+		self.reduce(size);
+		self.max_size = size;
+	}
+
+	fn rescale(&mut self, ratio: f64) {
+		self.count *= ratio;
+		self.hits *= ratio;
+	}
+
+	fn save(&self, writer: &mut dyn Write) -> Result<(), Error> {
+		// the hand is saved as its position in the queue, since the indices
+		// of the objects change when the queue is restored
+		let hand_position = self.hand
+			.and_then(|hand| self.queue.indices().position(|index| index == hand));
+
+		checkpoint::save(writer, &(self, hand_position))
+	}
+
+	fn restore(&mut self, reader: &mut dyn Read) -> Result<(), Error> {
+		let (cache, hand_position): (SieveCache, Option<usize>) = checkpoint::restore(reader)?;
+		*self = cache;
+
+		// the indices of the objects change when the lists are restored,
+		// so the map is rebuilt rather than saved
+		for index in self.queue.indices() {
+			self.map.insert(self.queue.get(index).unwrap().object.key, index);
+		}
+
+		self.hand = hand_position.and_then(|position| self.queue.indices().nth(position));
+
+		Ok(())
+	}
+}
+
+impl SieveObject {
+	fn new(access: &Access) -> Self {
+		SieveObject {
+			object: Object::new(access),
+			visited: false,
+		}
+	}
+}
@@ -0,0 +1,286 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::io::{Error, Read, Write};
+
+use rustc_hash::FxHashMap;
+use dlv_list::{VecList, Index};
+use serde::{Serialize, Deserialize};
+
+use crate::{
+	cache::{Cache, Object},
+	access::{Access, Key},
+	checkpoint,
+};
+
+/// A segmented LRU cache. New objects are inserted into the first
+/// (probationary) segment, and an object which is accessed is promoted to
+/// the next segment. When a segment exceeds its size, the objects at its
+/// tail are demoted to the head of the previous segment, and objects are
+/// evicted from the tail of the first segment.
+#[derive(Serialize, Deserialize)]
+pub struct SlruCache {
+	max_size: u64,
+
+	// the portion of the cache allocated to each segment
+	ratios: Vec<f64>,
+
+	count: f64,
+	hits: f64,
+
+	#[serde(skip)]
+	map: FxHashMap<Key, (usize, Index<Object>)>,
+	segments: Vec<Stack>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Stack {
+	stack: VecList<Object>,
+	size: u64,
+}
+
+impl SlruCache {
+	pub fn new(size: u64, ratios: &[f64]) -> Self {
+		assert!(!ratios.is_empty());
+		assert!(ratios.iter().all(|ratio| *ratio > 0.0));
+
+		let segments = ratios
+			.iter()
+			.map(|_| Stack::default())
+			.collect::<Vec<Stack>>();
+
+		SlruCache {
+			max_size: size,
+
+			ratios: ratios.to_vec(),
+
+			count: 0.0,
+			hits: 0.0,
+
+			map: FxHashMap::default(),
+			segments,
+		}
+	}
+}
+
+impl Cache for SlruCache {
+	fn size(&self) -> u64 {
+		self.max_size
+	}
+
+	fn miss_ratio(&self) -> f64 {
+		if self.count > 0.0 {
+			return 1.0 - self.hits / self.count;
+		}
+
+		0.0
+	}
+
+	fn increment_count(&mut self) {
+		self.count += 1.0
+	}
+
+	fn increment_hits(&mut self) {
+		self.hits += 1.0
+	}
+
+	fn clear_counters(&mut self) {
+		self.count = 0.0;
+		self.hits = 0.0;
+	}
+
+	fn process_get(&mut self, access: &Access) -> bool {
+		let Some(&(segment, index)) = self.map.get(&access.key) else {
+			return false;
+		};
+
+		let object = self.segments[segment].remove(index).unwrap();
+		let segment = (segment + 1).min(self.segments.len() - 1);
+
+		let index = self.segments[segment].push_front(object);
+		self.map.insert(access.key, (segment, index));
+
+		self.demote_objects();
+
+		true
+	}
+
+	fn process_set(&mut self, access: &Access) {
+		if access.size as u64 > self.max_size || self.has(access.key) {
+			return;
+		}
+
+		self.reduce(self.max_size - access.size as u64);
+
+		let object = Object::new(access);
+		let index = self.segments[0].push_front(object);
+
+		self.map.insert(access.key, (0, index));
+	}
+
+	fn process_del(&mut self, key: Key) {
+		if let Some((segment, index)) = self.map.remove(&key) {
+			self.segments[segment].remove(index);
+		}
+	}
+
+	fn process_has(&self, key: Key) -> bool {
+		self.map.contains_key(&key)
+	}
+
+	fn reduce(&mut self, target_size: u64) {
+		self.demote_objects();
+
+		while self.current_size() > target_size {
+			let Some(segment) = self.segments.iter_mut().find(|segment| !segment.is_empty()) else {
+				return;
+			};
+
+			if let Some(object) = segment.pop_back() {
+				self.map.remove(&object.key);
+			}
+		}
+	}
+
+	fn resize(&mut self, size: u64) {
+		self.max_size = size;
+		self.reduce(size);
+	}
+
+	fn rescale(&mut self, ratio: f64) {
+		self.count *= ratio;
+		self.hits *= ratio;
+	}
+
+	fn save(&self, writer: &mut dyn Write) -> Result<(), Error> {
+		checkpoint::save(writer, self)
+	}
+
+	fn restore(&mut self, reader: &mut dyn Read) -> Result<(), Error> {
+		*self = checkpoint::restore(reader)?;
+
+		// the indices of the objects change when the lists are restored,
+		// so the map is rebuilt rather than saved
+		for (segment, stack) in self.segments.iter().enumerate() {
+			for index in stack.stack.indices() {
+				self.map.insert(stack.stack.get(index).unwrap().key, (segment, index));
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl SlruCache {
+	fn current_size(&self) -> u64 {
+		self.segments
+			.iter()
+			.map(|segment| segment.size)
+			.sum()
+	}
+
+	fn segment_max_size(&self, segment: usize) -> u64 {
+		(self.ratios[segment] * self.max_size as f64) as u64
+	}
+
+	/// Moves objects from the tail of each segment (from the last segment
+	/// to the second) to the head of the previous segment until the segment
+	/// fits.
+	fn demote_objects(&mut self) {
+		for segment in (1..self.segments.len()).rev() {
+			while self.segments[segment].size > self.segment_max_size(segment) {
+				let object = self.segments[segment].pop_back().unwrap();
+				let key = object.key;
+
+				let index = self.segments[segment - 1].push_front(object);
+				self.map.insert(key, (segment - 1, index));
+			}
+		}
+	}
+}
+
+impl Stack {
+	fn is_empty(&self) -> bool {
+		self.stack.is_empty()
+	}
+
+	fn remove(&mut self, index: Index<Object>) -> Option<Object> {
+		let object = self.stack.remove(index);
+
+		if let Some(object) = &object {
+			self.size -= object.size as u64;
+		}
+
+		object
+	}
+
+	fn push_front(&mut self, object: Object) -> Index<Object> {
+		self.size += object.size as u64;
+		self.stack.push_front(object)
+	}
+
+	fn pop_back(&mut self) -> Option<Object> {
+		let object = self.stack.pop_back();
+
+		if let Some(object) = &object {
+			self.size -= object.size as u64;
+		}
+
+		object
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		access::{Access, Command},
+		cache::{Cache, SlruCache, LruCache},
+	};
+
+	fn access(timestamp: u64, key: u64) -> Access {
+		Access {
+			timestamp,
+			command: Command::Get,
+			key,
+			size: 1,
+			ttl: None,
+		}
+	}
+
+	fn miss_ratio(cache: &mut dyn Cache, trace: &[Access]) -> f64 {
+		for access in trace {
+			cache.handle_self_populating(access);
+		}
+
+		cache.miss_ratio()
+	}
+
+	#[test]
+	fn single_segment_is_lru() {
+		let trace = (0..5_000u64)
+			.map(|index| access(index + 1, index * 7 % 13 + index % 5 * 11))
+			.collect::<Vec<Access>>();
+
+		let lru_miss_ratio = miss_ratio(&mut LruCache::new(20), &trace);
+		let slru_miss_ratio = miss_ratio(&mut SlruCache::new(20, &[1.0]), &trace);
+
+		assert_eq!(lru_miss_ratio, slru_miss_ratio);
+	}
+
+	#[test]
+	fn segments_are_bounded() {
+		let mut cache = SlruCache::new(100, &[0.2, 0.3, 0.5]);
+
+		for index in 0..5_000u64 {
+			cache.handle_self_populating(&access(index + 1, index * 7 % 13 + index % 150));
+
+			assert!(cache.current_size() <= 100);
+			assert!(cache.segments[1].size <= 30);
+			assert!(cache.segments[2].size <= 50);
+		}
+	}
+}
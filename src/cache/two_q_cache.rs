@@ -5,14 +5,19 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use std::io::{Error, Read, Write};
+
 use rustc_hash::FxHashMap;
 use dlv_list::{VecList, Index};
+use serde::{Serialize, Deserialize};
 
 use crate::{
 	cache::{Cache, Object},
 	access::{Access, Key},
+	checkpoint,
 };
 
+#[derive(Serialize, Deserialize)]
 pub struct TwoQCache {
 	max_size: u64,
 
@@ -22,6 +27,7 @@ pub struct TwoQCache {
 	count: f64,
 	hits: f64,
 
+	#[serde(skip)]
 	map: FxHashMap<Key, StackIndex>,
 
 	ain: Stack,
@@ -29,7 +35,7 @@ pub struct TwoQCache {
 	am: Stack,
 }
 
-#[derive(Default)]
+#[derive(Default, Serialize, Deserialize)]
 struct Stack {
 	stack: VecList<Object>,
 	size: u64,
@@ -149,7 +155,9 @@ impl Cache for TwoQCache {
 	}
 
 	fn reduce(&mut self, target_size: u64) {
-		let object_size = (self.max_size - target_size) as u32;
+		// the cache may be growing (i.e., the target is above the current
+		// maximum), in which case no space needs to be made in Ain
+		let object_size = self.max_size.saturating_sub(target_size) as u32;
 
 		while !self.ain.is_empty() && !self.can_ain_fit(object_size) {
 			self.promote_ain_object();
@@ -175,6 +183,30 @@ impl Cache for TwoQCache {
 		self.count *= ratio;
 		self.hits *= ratio;
 	}
+
+	fn save(&self, writer: &mut dyn Write) -> Result<(), Error> {
+		checkpoint::save(writer, self)
+	}
+
+	fn restore(&mut self, reader: &mut dyn Read) -> Result<(), Error> {
+		*self = checkpoint::restore(reader)?;
+
+		// the indices of the objects change when the lists are restored,
+		// so the map is rebuilt rather than saved
+		for index in self.ain.stack.indices() {
+			self.map.insert(self.ain.stack.get(index).unwrap().key, StackIndex::Ain(index));
+		}
+
+		for index in self.aout.stack.indices() {
+			self.map.insert(self.aout.stack.get(index).unwrap().key, StackIndex::Aout(index));
+		}
+
+		for index in self.am.stack.indices() {
+			self.map.insert(self.am.stack.get(index).unwrap().key, StackIndex::Am(index));
+		}
+
+		Ok(())
+	}
 }
 
 impl TwoQCache {
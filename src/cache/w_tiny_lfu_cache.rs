@@ -0,0 +1,548 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::io::{Error, Read, Write};
+
+use rustc_hash::FxHashMap;
+use dlv_list::{VecList, Index};
+use serde::{Serialize, Deserialize};
+
+use crate::{
+	cache::{Cache, Object},
+	access::{Access, Key},
+	checkpoint,
+};
+
+// the portion of the main area which is protected
+const PROTECTED_RATIO: f64 = 0.8;
+
+// the number of rows in the Count-Min sketch
+const SKETCH_DEPTH: usize = 4;
+
+// the minimum number of counters in each row of the Count-Min sketch
+const SKETCH_MIN_WIDTH: usize = 1024;
+
+// the maximum value of a counter in the Count-Min sketch
+const SKETCH_MAX_COUNT: u8 = 15;
+
+// the seeds used to hash keys into each row of the Count-Min sketch
+const SKETCH_SEEDS: [u64; SKETCH_DEPTH] = [
+	0x97cb3127,
+	0xb6b4ab74,
+	0x8ec2a6e5,
+	0xe8a9a1d3,
+];
+
+/// A W-TinyLFU cache, which consists of an LRU admission window and a
+/// segmented LRU main area (split into probation and protected segments).
+/// Objects evicted from the window are only admitted into the main area
+/// if their estimated frequency is greater than that of the main area's
+/// victim.
+#[derive(Serialize, Deserialize)]
+pub struct WTinyLfuCache {
+	max_size: u64,
+	window_ratio: f64,
+
+	count: f64,
+	hits: f64,
+
+	#[serde(skip)]
+	map: FxHashMap<Key, StackIndex>,
+
+	window: Stack,
+	probation: Stack,
+	protected: Stack,
+
+	sketch: CountMinSketch,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Stack {
+	stack: VecList<Object>,
+	size: u64,
+}
+
+enum StackIndex {
+	Window(Index<Object>),
+	Probation(Index<Object>),
+	Protected(Index<Object>),
+}
+
+/// A Count-Min sketch of 4-bit counters which estimates the frequency of
+/// each key. Every counter is halved once the number of increments reaches
+/// the sample size so that the sketch favours recent frequency.
+#[derive(Serialize, Deserialize)]
+struct CountMinSketch {
+	table: Vec<u8>,
+	width: usize,
+
+	additions: u64,
+	sample_size: u64,
+}
+
+impl WTinyLfuCache {
+	pub fn new(size: u64, window_ratio: f64) -> Self {
+		assert!(window_ratio > 0.0);
+		assert!(window_ratio < 1.0);
+
+		WTinyLfuCache {
+			max_size: size,
+			window_ratio,
+
+			count: 0.0,
+			hits: 0.0,
+
+			map: FxHashMap::default(),
+
+			window: Stack::default(),
+			probation: Stack::default(),
+			protected: Stack::default(),
+
+			sketch: CountMinSketch::new(SKETCH_MIN_WIDTH),
+		}
+	}
+}
+
+impl Cache for WTinyLfuCache {
+	fn size(&self) -> u64 {
+		self.max_size
+	}
+
+	fn miss_ratio(&self) -> f64 {
+		if self.count > 0.0 {
+			return 1.0 - self.hits / self.count;
+		}
+
+		0.0
+	}
+
+	fn increment_count(&mut self) {
+		self.count += 1.0
+	}
+
+	fn increment_hits(&mut self) {
+		self.hits += 1.0
+	}
+
+	fn clear_counters(&mut self) {
+		self.count = 0.0;
+		self.hits = 0.0;
+	}
+
+	fn process_get(&mut self, access: &Access) -> bool {
+		self.sketch.increment(access.key);
+
+		let Some(stack_index) = self.map.get(&access.key) else {
+			return false;
+		};
+
+		match stack_index {
+			StackIndex::Window(index) => {
+				let object = self.window.remove(*index).unwrap();
+				let index = self.window.push_front(object);
+
+				self.map.insert(access.key, StackIndex::Window(index));
+			},
+
+			StackIndex::Probation(index) => {
+				let object = self.probation.remove(*index).unwrap();
+				let index = self.protected.push_front(object);
+
+				self.map.insert(access.key, StackIndex::Protected(index));
+				self.demote_protected_objects();
+			},
+
+			StackIndex::Protected(index) => {
+				let object = self.protected.remove(*index).unwrap();
+				let index = self.protected.push_front(object);
+
+				self.map.insert(access.key, StackIndex::Protected(index));
+			},
+		};
+
+		true
+	}
+
+	fn process_set(&mut self, access: &Access) {
+		if access.size as u64 > self.max_size || self.has(access.key) {
+			return;
+		}
+
+		let object = Object::new(access);
+		let index = self.window.push_front(object);
+
+		self.map.insert(access.key, StackIndex::Window(index));
+		self.sketch.ensure_capacity(self.map.len());
+
+		self.reduce(self.max_size);
+	}
+
+	fn process_del(&mut self, key: Key) {
+		let Some(stack_index) = self.map.remove(&key) else {
+			return;
+		};
+
+		match stack_index {
+			StackIndex::Window(index) => self.window.remove(index),
+			StackIndex::Probation(index) => self.probation.remove(index),
+			StackIndex::Protected(index) => self.protected.remove(index),
+		};
+	}
+
+	fn process_has(&self, key: Key) -> bool {
+		self.map.contains_key(&key)
+	}
+
+	fn reduce(&mut self, target_size: u64) {
+		while self.window.size > self.window_max_size() {
+			self.evict_window();
+		}
+
+		while self.current_size() > target_size {
+			if let Some(object) = self.probation.pop_back() {
+				self.map.remove(&object.key);
+			} else if let Some(object) = self.protected.pop_back() {
+				self.map.remove(&object.key);
+			} else if let Some(object) = self.window.pop_back() {
+				self.map.remove(&object.key);
+			} else {
+				return;
+			}
+		}
+	}
+
+	fn resize(&mut self, size: u64) {
+		self.max_size = size;
+		self.reduce(size);
+
+		self.demote_protected_objects();
+	}
+
+	fn rescale(&mut self, ratio: f64) {
+		self.count *= ratio;
+		self.hits *= ratio;
+	}
+
+	fn save(&self, writer: &mut dyn Write) -> Result<(), Error> {
+		checkpoint::save(writer, self)
+	}
+
+	fn restore(&mut self, reader: &mut dyn Read) -> Result<(), Error> {
+		*self = checkpoint::restore(reader)?;
+
+		// the indices of the objects change when the lists are restored,
+		// so the map is rebuilt rather than saved
+		for index in self.window.stack.indices() {
+			self.map.insert(self.window.stack.get(index).unwrap().key, StackIndex::Window(index));
+		}
+
+		for index in self.probation.stack.indices() {
+			self.map.insert(self.probation.stack.get(index).unwrap().key, StackIndex::Probation(index));
+		}
+
+		for index in self.protected.stack.indices() {
+			self.map.insert(self.protected.stack.get(index).unwrap().key, StackIndex::Protected(index));
+		}
+
+		Ok(())
+	}
+}
+
+impl WTinyLfuCache {
+	fn current_size(&self) -> u64 {
+		self.window.size + self.probation.size + self.protected.size
+	}
+
+	fn window_max_size(&self) -> u64 {
+		(self.window_ratio * self.max_size as f64) as u64
+	}
+
+	fn protected_max_size(&self) -> u64 {
+		let main_max_size = self.max_size - self.window_max_size();
+		(PROTECTED_RATIO * main_max_size as f64) as u64
+	}
+
+	/// Moves objects from the tail of the protected segment to the head of
+	/// the probation segment until the protected segment fits.
+	fn demote_protected_objects(&mut self) {
+		while self.protected.size > self.protected_max_size() {
+			let object = self.protected.pop_back().unwrap();
+			let key = object.key;
+
+			let index = self.probation.push_front(object);
+			self.map.insert(key, StackIndex::Probation(index));
+		}
+	}
+
+	/// Evicts the object at the tail of the window. The object (i.e., the
+	/// candidate) is admitted into the probation segment if its estimated
+	/// frequency is greater than that of each victim which must be evicted
+	/// from the main area to make room for it. Otherwise, it is rejected
+	/// and no victim is evicted.
+	fn evict_window(&mut self) {
+		let Some(candidate) = self.window.pop_back() else {
+			return;
+		};
+
+		let candidate_frequency = self.sketch.frequency(candidate.key);
+		let current_size = self.current_size();
+
+		let mut victims = self.probation.stack
+			.iter()
+			.rev()
+			.chain(self.protected.stack.iter().rev());
+
+		let mut victim_keys = Vec::<Key>::new();
+		let mut victims_size: u64 = 0;
+
+		while current_size - victims_size + candidate.size as u64 > self.max_size {
+			let Some(victim) = victims.next() else {
+				break;
+			};
+
+			if candidate_frequency <= self.sketch.frequency(victim.key) {
+				self.map.remove(&candidate.key);
+				return;
+			}
+
+			victim_keys.push(victim.key);
+			victims_size += victim.size as u64;
+		}
+
+		for key in victim_keys {
+			self.process_del(key);
+		}
+
+		let key = candidate.key;
+		let index = self.probation.push_front(candidate);
+
+		self.map.insert(key, StackIndex::Probation(index));
+	}
+}
+
+impl Stack {
+	fn remove(&mut self, index: Index<Object>) -> Option<Object> {
+		let object = self.stack.remove(index);
+
+		if let Some(object) = &object {
+			self.size -= object.size as u64;
+		}
+
+		object
+	}
+
+	fn push_front(&mut self, object: Object) -> Index<Object> {
+		self.size += object.size as u64;
+		self.stack.push_front(object)
+	}
+
+	fn pop_back(&mut self) -> Option<Object> {
+		let object = self.stack.pop_back();
+
+		if let Some(object) = &object {
+			self.size -= object.size as u64;
+		}
+
+		object
+	}
+}
+
+impl CountMinSketch {
+	fn new(width: usize) -> Self {
+		let width = width.next_power_of_two();
+
+		CountMinSketch {
+			table: vec![0; SKETCH_DEPTH * width],
+			width,
+
+			additions: 0,
+			sample_size: 10 * width as u64,
+		}
+	}
+
+	/// Grows the sketch if the number of objects exceeds the width of the
+	/// sketch. Since the width is a power of two, the index of a key in a
+	/// row of the wider sketch has the same low bits as its current index,
+	/// so each counter is copied to the counters which it covers and the
+	/// estimated frequencies are unchanged.
+	fn ensure_capacity(&mut self, num_objects: usize) {
+		if num_objects <= self.width {
+			return;
+		}
+
+		let width = num_objects.next_power_of_two();
+		let mut table = vec![0; SKETCH_DEPTH * width];
+
+		for row in 0..SKETCH_DEPTH {
+			for column in 0..width {
+				table[row * width + column] = self.table[row * self.width + (column & (self.width - 1))];
+			}
+		}
+
+		self.table = table;
+		self.width = width;
+		self.sample_size = 10 * width as u64;
+	}
+
+	fn frequency(&self, key: Key) -> u8 {
+		(0..SKETCH_DEPTH)
+			.map(|row| self.table[self.index(key, row)])
+			.min()
+			.unwrap_or(0)
+	}
+
+	fn increment(&mut self, key: Key) {
+		let mut incremented = false;
+
+		for row in 0..SKETCH_DEPTH {
+			let index = self.index(key, row);
+
+			if self.table[index] < SKETCH_MAX_COUNT {
+				self.table[index] += 1;
+				incremented = true;
+			}
+		}
+
+		if incremented {
+			self.additions += 1;
+		}
+
+		if self.additions >= self.sample_size {
+			self.age();
+		}
+	}
+
+	/// Halves every counter in the sketch.
+	fn age(&mut self) {
+		for count in self.table.iter_mut() {
+			*count /= 2;
+		}
+
+		self.additions /= 2;
+	}
+
+	fn index(&self, key: Key, row: usize) -> usize {
+		let mut hash = key.wrapping_add(SKETCH_SEEDS[row]).wrapping_mul(0x9e3779b97f4a7c15);
+
+		hash ^= hash >> 32;
+		hash = hash.wrapping_mul(0xbf58476d1ce4e5b9);
+		hash ^= hash >> 29;
+
+		row * self.width + (hash as usize & (self.width - 1))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		access::{Access, Command},
+		cache::{
+			Cache,
+			WTinyLfuCache,
+			LruCache,
+			Object,
+			w_tiny_lfu_cache::{CountMinSketch, StackIndex},
+		},
+	};
+
+	fn access(timestamp: u64, key: u64) -> Access {
+		Access {
+			timestamp,
+			command: Command::Get,
+			key,
+			size: 1,
+			ttl: None,
+		}
+	}
+
+	fn miss_ratio(cache: &mut dyn Cache, trace: &[Access]) -> f64 {
+		for access in trace {
+			cache.handle_self_populating(access);
+		}
+
+		cache.miss_ratio()
+	}
+
+	#[test]
+	fn hot_set_survives_scan() {
+		let mut trace = Vec::<Access>::new();
+
+		for index in 0..20_000u64 {
+			let key = match index % 4 {
+				0 => index / 4 % 30,
+				_ => 1_000 + index,
+			};
+
+			trace.push(access(index + 1, key));
+		}
+
+		let lru_miss_ratio = miss_ratio(&mut LruCache::new(60), &trace);
+		let w_tiny_lfu_miss_ratio = miss_ratio(&mut WTinyLfuCache::new(60, 0.01), &trace);
+
+		assert_eq!(lru_miss_ratio, 1.0);
+		assert!(w_tiny_lfu_miss_ratio < 0.8);
+	}
+
+	#[test]
+	fn size_is_bounded() {
+		let mut cache = WTinyLfuCache::new(100, 0.1);
+
+		for index in 0..1_000u64 {
+			cache.handle_self_populating(&access(index + 1, index % 300));
+			assert!(cache.current_size() <= 100);
+			assert!(cache.window.size <= 10);
+		}
+	}
+
+	#[test]
+	fn rejected_candidate_evicts_nothing() {
+		let mut cache = WTinyLfuCache::new(4, 0.25);
+
+		// 2 is at the tail of the probation segment, followed by 1
+		for key in [2, 1] {
+			let index = cache.probation.push_front(Object::new(&access(0, key)));
+			cache.map.insert(key, StackIndex::Probation(index));
+		}
+
+		let candidate = Access {
+			size: 4,
+			..access(0, 3)
+		};
+
+		let index = cache.window.push_front(Object::new(&candidate));
+		cache.map.insert(3, StackIndex::Window(index));
+
+		for (key, count) in [(1, 5), (3, 2)] {
+			for _ in 0..count {
+				cache.sketch.increment(key);
+			}
+		}
+
+		// the candidate is more frequent than 2 but not 1, so both must
+		// remain in the cache
+		cache.evict_window();
+
+		assert!(cache.has(1));
+		assert!(cache.has(2));
+		assert!(!cache.has(3));
+		assert_eq!(cache.current_size(), 2);
+	}
+
+	#[test]
+	fn sketch_growth_keeps_frequencies() {
+		let mut sketch = CountMinSketch::new(4);
+
+		for _ in 0..3 {
+			sketch.increment(7);
+		}
+
+		sketch.ensure_capacity(100);
+
+		assert_eq!(sketch.width, 128);
+		assert_eq!(sketch.frequency(7), 3);
+	}
+}
@@ -0,0 +1,267 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::{
+	fs::{self, File},
+	io::{BufReader, BufWriter, Error, ErrorKind, Read, Write},
+	path::Path,
+};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+	algorithm::Algorithm,
+	shards::Shards,
+};
+
+const MAGIC: [u8; 8] = *b"KOSMOCKP";
+const VERSION: u32 = 2;
+
+/// Saves the state of the algorithm to a checkpoint file, along with the
+/// number of accesses of the trace which have been read. The checkpoint is
+/// written to a temporary file first, so a crash while saving never
+/// corrupts the previous checkpoint.
+pub fn save_checkpoint(
+	path: &str,
+	offset: u64,
+	algorithm: &dyn Algorithm,
+) -> Result<(), Error> {
+	let temp_path = format!("{path}.tmp");
+	let mut writer = BufWriter::new(File::create(&temp_path)?);
+
+	writer.write_all(&MAGIC)?;
+	save(&mut writer, &VERSION)?;
+	save(&mut writer, &offset)?;
+
+	algorithm.save(&mut writer)?;
+
+	writer.into_inner()?.sync_all()?;
+	fs::rename(&temp_path, path)
+}
+
+/// Restores the state of the algorithm from a checkpoint file, returning
+/// the number of accesses of the trace which had been read. The algorithm
+/// must be configured as it was when the checkpoint was saved.
+pub fn restore_checkpoint(
+	path: &str,
+	algorithm: &mut dyn Algorithm,
+) -> Result<u64, Error> {
+	if !Path::new(path).exists() {
+		return Err(Error::new(
+			ErrorKind::NotFound,
+			"Checkpoint file not found.",
+		));
+	}
+
+	let mut reader = BufReader::new(File::open(path)?);
+	let mut magic = [0u8; 8];
+
+	reader.read_exact(&mut magic)?;
+
+	if magic != MAGIC || restore::<u32>(&mut reader)? != VERSION {
+		return Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid checkpoint file.",
+		));
+	}
+
+	let offset = restore::<u64>(&mut reader)?;
+
+	algorithm.restore(&mut reader)?;
+
+	Ok(offset)
+}
+
+/// Writes a value to a checkpoint.
+pub fn save<T: Serialize + ?Sized>(
+	writer: &mut dyn Write,
+	value: &T,
+) -> Result<(), Error> {
+	bincode::serialize_into(writer, value)
+		.map_err(|err| Error::new(ErrorKind::InvalidData, err))
+}
+
+/// Reads a value from a checkpoint.
+pub fn restore<T: DeserializeOwned>(reader: &mut dyn Read) -> Result<T, Error> {
+	bincode::deserialize_from(reader)
+		.map_err(|err| Error::new(ErrorKind::InvalidData, err))
+}
+
+/// Writes the state of the (optional) SHARDS sampler to a checkpoint.
+pub fn save_shards(
+	writer: &mut dyn Write,
+	shards: Option<&dyn Shards>,
+) -> Result<(), Error> {
+	save(writer, &shards.is_some())?;
+
+	match shards {
+		Some(shards) => shards.save(writer),
+		None => Ok(()),
+	}
+}
+
+/// Restores the state of the (optional) SHARDS sampler from a checkpoint.
+pub fn restore_shards(
+	reader: &mut dyn Read,
+	shards: &mut Option<Box<dyn Shards>>,
+) -> Result<(), Error> {
+	if restore::<bool>(reader)? != shards.is_some() {
+		return Err(Error::new(
+			ErrorKind::InvalidData,
+			"The checkpoint was saved with a different SHARDS configuration.",
+		));
+	}
+
+	match shards {
+		Some(shards) => shards.restore(reader),
+		None => Ok(()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{slice, str::FromStr};
+
+	use crate::{
+		access::{Access, Command},
+		algorithm::Algorithm,
+		cache::CachePolicy,
+		curve::Interpolation,
+		checkpoint,
+		kosmo::{Kosmo, KosmoPolicy},
+		minisimulations::Minisimulations,
+		shards::ShardsFixedSize,
+	};
+
+	const NUM_ACCESSES: u64 = 4_000;
+	const NUM_KEYS: u64 = 300;
+
+	fn trace() -> Vec<Access> {
+		let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+
+		(0..NUM_ACCESSES)
+			.map(|index| {
+				state ^= state << 13;
+				state ^= state >> 7;
+				state ^= state << 17;
+
+				let key = (state % NUM_KEYS) * (state % 3 + 1) % NUM_KEYS;
+
+				Access {
+					timestamp: index + 1,
+					command: Command::Get,
+					key,
+					size: (key % 8 + 1) as u32 * 512,
+					ttl: None,
+				}
+			})
+			.collect()
+	}
+
+	/// Runs the trace through the algorithm without interruption, and again
+	/// with a checkpoint halfway through which is restored into a new
+	/// instance, and checks that the resulting curves of every policy are
+	/// identical.
+	fn check_resume(init: impl Fn() -> Box<dyn Algorithm>) {
+		let trace = trace();
+		let (first_half, second_half) = trace.split_at(trace.len() / 2);
+
+		let mut uninterrupted = init();
+
+		for access in &trace {
+			uninterrupted.handle(access);
+		}
+
+		let mut interrupted = init();
+
+		for access in first_half {
+			interrupted.handle(access);
+		}
+
+		let mut state = Vec::<u8>::new();
+		interrupted.save(&mut state).unwrap();
+		drop(interrupted);
+
+		let mut resumed = init();
+		resumed.restore(&mut state.as_slice()).unwrap();
+
+		for access in second_half {
+			resumed.handle(access);
+		}
+
+		let expected_curves = uninterrupted.curves();
+		let actual_curves = resumed.curves();
+
+		assert_eq!(expected_curves.len(), actual_curves.len());
+
+		for (expected, actual) in expected_curves.iter().zip(&actual_curves) {
+			assert!(!expected.is_empty());
+			assert_eq!(expected.size(), actual.size());
+			assert_eq!(expected.mae(actual, Interpolation::Step), 0.0);
+		}
+	}
+
+	#[test]
+	fn kosmo_resumes_identically() {
+		let policies = ["lru", "lfu", "fifo", "2q", "lrfu", "sieve", "s3fifo", "lirs", "gdsf", "lruk-2-5", "slru"]
+			.map(|policy| KosmoPolicy::from_str(policy).unwrap());
+
+		check_resume(|| Box::new(Kosmo::new(&policies, None)));
+
+		check_resume(|| Box::new(Kosmo::new(
+			&policies[..1],
+			Some(Box::new(ShardsFixedSize::new(1 << 23, 100))),
+		)));
+	}
+
+	#[test]
+	fn minisim_resumes_identically() {
+		let policies = [
+			"lru", "lfu", "fifo", "2q-0.25-0.5", "lrfu-2-0.5", "sieve", "s3fifo",
+			"arc", "lirs", "gds-size", "gdsf", "wtinylfu", "lruk-2-5", "slru",
+		];
+
+		let policies = policies.map(|policy| CachePolicy::from_str(policy).unwrap());
+
+		for policy in &policies {
+			check_resume(|| Box::new(Minisimulations::new(slice::from_ref(policy), 1 << 20, None)));
+		}
+
+		check_resume(|| Box::new(Minisimulations::new(&policies, 1 << 20, None)));
+
+		check_resume(|| Box::new(Minisimulations::new(
+			&policies[..1],
+			1 << 20,
+			Some(Box::new(ShardsFixedSize::new(1 << 23, 100))),
+		)));
+	}
+
+	#[test]
+	fn checkpoint_file_stores_offset() {
+		let path = std::env::temp_dir()
+			.join(format!("kosmo-checkpoint-{}", std::process::id()))
+			.to_string_lossy()
+			.to_string();
+
+		let mut kosmo = Kosmo::new(&[KosmoPolicy::Lru], None);
+
+		for access in trace().iter().take(100) {
+			kosmo.handle(access);
+		}
+
+		checkpoint::save_checkpoint(&path, 100, &kosmo).unwrap();
+
+		let mut restored = Kosmo::new(&[KosmoPolicy::Lru], None);
+		assert_eq!(checkpoint::restore_checkpoint(&path, &mut restored).unwrap(), 100);
+
+		// the policies must match those of the checkpoint
+		let mut other = Kosmo::new(&[KosmoPolicy::Lfu], None);
+		assert!(checkpoint::restore_checkpoint(&path, &mut other).is_err());
+
+		std::fs::remove_file(&path).unwrap();
+	}
+}
@@ -0,0 +1,184 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use clap::Parser;
+use kwik::fmt;
+
+use kosmo_fast24::{
+	curve::{Curve, Interpolation},
+	curve_plot::{CurvePlot, AxisScale},
+	curve_comparison::{CurveComparison, CurveErrors},
+	curve_set,
+	grid::SizeGrid,
+};
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+	/// The curve files to compare. Each path may be a glob pattern (quoted,
+	/// so that it is not expanded by the shell), whose matches are loaded
+	/// in sorted order.
+	#[arg(short, long, num_args = 1.., required = true)]
+	path: Vec<String>,
+
+	/// The labels of the curves, in the order in which they are loaded.
+	/// Curves without a label are labelled by their file name.
+	#[arg(short, long, num_args = 1..)]
+	label: Vec<String>,
+
+	/// The label of the curve against which the others are compared
+	/// (the first curve by default).
+	#[arg(short, long)]
+	reference: Option<String>,
+
+	/// The output path of the overlay plot of all curves (saved as SVG or
+	/// PNG if the path ends in .svg or .png, and as PDF otherwise).
+	#[arg(short, long)]
+	output: String,
+
+	/// The scale of the x axis of the plot (linear or log).
+	#[arg(long, default_value = "linear")]
+	x_scale: AxisScale,
+
+	/// Adds a subplot of the absolute error of each curve compared to the
+	/// reference.
+	#[arg(long)]
+	error_plot: bool,
+
+	/// The interpolation between the points of the curves (step or
+	/// linear).
+	#[arg(short, long, default_value = "step")]
+	interpolation: Interpolation,
+
+	/// The sizes at which the curves are compared (linear[-<points>],
+	/// log[-<points>[-<min size>]], or a path to a file with one size per
+	/// line).
+	#[arg(short, long, default_value = "linear")]
+	grid: SizeGrid,
+}
+
+fn main() {
+	let args = Args::parse();
+
+	let paths = expand_paths(&args.path);
+
+	assert!(
+		args.label.len() <= paths.len(),
+		"There are more labels ({}) than curves ({}).",
+		args.label.len(),
+		paths.len(),
+	);
+
+	let curves = paths
+		.iter()
+		.enumerate()
+		.map(|(index, path)| {
+			let label = args.label
+				.get(index)
+				.cloned()
+				.unwrap_or_else(|| curve_set::get_file_label(path));
+
+			let curve = Curve::from_file(path)
+				.unwrap_or_else(|err| panic!("Could not load curve {path}: {err}"));
+
+			(label, curve)
+		})
+		.collect::<Vec<(String, Curve)>>();
+
+	let reference_index = match &args.reference {
+		Some(reference) => curves
+			.iter()
+			.position(|(label, _)| label == reference)
+			.unwrap_or_else(|| panic!("There is no curve labelled {reference}.")),
+
+		None => 0,
+	};
+
+	let (reference_label, reference_curve) = &curves[reference_index];
+
+	let comparison = CurveComparison::new()
+		.with_grid(args.grid.clone())
+		.with_interpolation(args.interpolation);
+
+	let rows = curves
+		.iter()
+		.enumerate()
+		.filter(|(index, _)| *index != reference_index)
+		.map(|(_, (label, curve))| (label.as_str(), comparison.compare(reference_curve, curve)))
+		.collect::<Vec<(&str, CurveErrors)>>();
+
+	println!("Reference: {reference_label}");
+	print_table(&rows);
+
+	let mut curve_plot = CurvePlot::default();
+
+	curve_plot.set_x_scale(args.x_scale);
+	curve_plot.set_error_plot(args.error_plot);
+	curve_plot.set_reference(reference_curve.clone(), reference_label);
+
+	for (index, (label, curve)) in curves.iter().enumerate() {
+		if index != reference_index {
+			curve_plot.add(curve.clone(), Some(label.as_str()));
+		}
+	}
+
+	curve_plot
+		.save(&args.output, args.interpolation)
+		.expect("Could not save figure.");
+}
+
+/// Expands the glob patterns of the supplied paths. Paths which are not
+/// patterns are kept as they are.
+fn expand_paths(patterns: &[String]) -> Vec<String> {
+	let mut paths = Vec::<String>::new();
+
+	for pattern in patterns {
+		if !pattern.contains(['*', '?', '[']) {
+			paths.push(pattern.clone());
+			continue;
+		}
+
+		let mut matches = glob::glob(pattern)
+			.unwrap_or_else(|err| panic!("Invalid glob pattern {pattern}: {err}"))
+			.filter_map(Result::ok)
+			.map(|path| path.to_string_lossy().to_string())
+			.collect::<Vec<String>>();
+
+		assert!(!matches.is_empty(), "No curves match {pattern}.");
+
+		matches.sort();
+		paths.extend(matches);
+	}
+
+	paths
+}
+
+fn print_table(rows: &[(&str, CurveErrors)]) {
+	let label_width = rows
+		.iter()
+		.map(|(label, _)| label.len())
+		.chain(["Curve".len()])
+		.max()
+		.unwrap();
+
+	println!(
+		"{:<label_width$}  {:>10}  {:>10}  {:>10}  {:>12}  {:>14}",
+		"Curve", "MAE", "RMSE", "Max error", "Max error at", "Area",
+	);
+
+	for (label, errors) in rows {
+		println!(
+			"{:<label_width$}  {:>10.6}  {:>10.6}  {:>10.6}  {:>12}  {:>14.2}",
+			label,
+			errors.mae,
+			errors.rmse,
+			errors.max_error,
+			fmt::memory(errors.max_error_size, Some(2)),
+			errors.area,
+		);
+	}
+}
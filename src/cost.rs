@@ -0,0 +1,206 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use crate::curve::{Curve, Interpolation};
+
+// memory is priced per (decimal) gigabyte, as cloud providers price it
+const GB: f64 = 1_000_000_000.0;
+const SECONDS_PER_HOUR: f64 = 3600.0;
+
+/// The hourly cost of a cache, made up of the memory which is provisioned
+/// for it and the misses which go to the backend. The memory price and the
+/// miss cost must be in the same unit (e.g., dollars, or seconds of latency
+/// if memory is priced in latency).
+#[derive(Clone, Debug)]
+pub struct CostModel {
+	request_rate: f64,
+	memory_price: f64,
+	miss_cost: f64,
+	instance_size: Option<u64>,
+}
+
+/// The hourly cost of a cache of some size.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Cost {
+	/// The size of the cache.
+	pub size: u64,
+
+	/// The size of memory which is provisioned (and paid for) to hold
+	/// the cache.
+	pub provisioned_size: u64,
+
+	pub memory_cost: f64,
+	pub miss_cost: f64,
+	pub total_cost: f64,
+}
+
+impl CostModel {
+	/// Returns a cost model of a cache which receives the supplied number
+	/// of requests per second, where memory costs the supplied price per
+	/// GB-hour (of 10^9 bytes) and each miss costs the supplied amount.
+	pub fn new(request_rate: f64, memory_price: f64, miss_cost: f64) -> Self {
+		assert!(request_rate >= 0.0 && memory_price >= 0.0 && miss_cost >= 0.0);
+
+		CostModel {
+			request_rate,
+			memory_price,
+			miss_cost,
+			instance_size: None,
+		}
+	}
+
+	/// Sets the size (in bytes) of the instances in which memory is
+	/// provisioned, so that the cost of a cache includes the unused memory
+	/// of its last instance.
+	pub fn with_instance_size(mut self, instance_size: u64) -> Self {
+		assert!(instance_size > 0, "The instance size must be positive.");
+
+		self.instance_size = Some(instance_size);
+		self
+	}
+
+	/// Returns the hourly cost of a cache of the supplied size.
+	pub fn cost(&self, curve: &Curve, size: u64, interpolation: Interpolation) -> Cost {
+		let provisioned_size = match self.instance_size {
+			Some(instance_size) => size.div_ceil(instance_size) * instance_size,
+			None => size,
+		};
+
+		let memory_cost = provisioned_size as f64 / GB * self.memory_price;
+
+		// the cache can use all of the memory which is provisioned for it
+		let miss_ratio = curve.get_interpolated_miss_ratio(provisioned_size, interpolation);
+		let miss_cost = self.request_rate * SECONDS_PER_HOUR * miss_ratio * self.miss_cost;
+
+		Cost {
+			size,
+			provisioned_size,
+			memory_cost,
+			miss_cost,
+			total_cost: memory_cost + miss_cost,
+		}
+	}
+
+	/// Returns the hourly cost at each candidate size: each multiple of the
+	/// instance size up to (and including the first past) the maximum size
+	/// of the MRC, or, without an instance size, zero and each size of the
+	/// MRC's points (where the cost of a step or linear MRC is minimized).
+	pub fn costs(&self, curve: &Curve, interpolation: Interpolation) -> Vec<Cost> {
+		let sizes = match self.instance_size {
+			Some(instance_size) => {
+				let num_instances = curve.get_max_size().div_ceil(instance_size);
+
+				(0..=num_instances)
+					.map(|instances| instances * instance_size)
+					.collect::<Vec<u64>>()
+			},
+
+			None => [0]
+				.into_iter()
+				.chain(curve.into_iter().map(|point| point.get_size()))
+				.collect::<Vec<u64>>(),
+		};
+
+		sizes
+			.into_iter()
+			.map(|size| self.cost(curve, size, interpolation))
+			.collect()
+	}
+
+	/// Returns the candidate size with the lowest hourly cost.
+	pub fn optimal(&self, curve: &Curve, interpolation: Interpolation) -> Cost {
+		self.costs(curve, interpolation)
+			.into_iter()
+			.min_by(|cost, other| cost.total_cost.total_cmp(&other.total_cost))
+			.unwrap_or_default()
+	}
+
+	/// Returns the hourly cost at each candidate size, relative to the
+	/// highest cost (so that it can be plotted alongside MRCs with
+	/// `CurvePlot`).
+	pub fn cost_curve(&self, curve: &Curve, interpolation: Interpolation) -> Curve {
+		let costs = self.costs(curve, interpolation);
+
+		let max_cost = costs
+			.iter()
+			.map(|cost| cost.total_cost)
+			.fold(0.0_f64, f64::max);
+
+		let mut cost_curve = Curve::new();
+
+		for cost in costs {
+			let relative_cost = match max_cost > 0.0 {
+				true => cost.total_cost / max_cost,
+				false => 0.0,
+			};
+
+			cost_curve.add(cost.size, relative_cost);
+		}
+
+		cost_curve
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		cost::CostModel,
+		curve::{Curve, Interpolation},
+		test_utils::assert_close,
+	};
+
+	const GB: u64 = 1_000_000_000;
+
+	fn curve() -> Curve {
+		Curve::from_points(&[(GB, 0.5), (2 * GB, 0.1), (3 * GB, 0.09), (4 * GB, 0.08)])
+	}
+
+	#[test]
+	fn optimal_size_balances_memory_and_misses() {
+		// 1000 requests/s for an hour is 3.6M requests, so each 1% of misses
+		// costs 36 at a miss cost of 0.001
+		let model = CostModel::new(1_000.0, 100.0, 0.001);
+		let optimal = model.optimal(&curve(), Interpolation::Step);
+
+		assert_eq!(optimal.size, 2 * GB);
+		assert_close(optimal.memory_cost, 200.0);
+		assert_close(optimal.miss_cost, 360.0);
+
+		// expensive memory favours a smaller cache
+		let model = CostModel::new(1_000.0, 2_000.0, 0.001);
+		assert_eq!(model.optimal(&curve(), Interpolation::Step).size, 0);
+	}
+
+	#[test]
+	fn instances_are_paid_in_full() {
+		let model = CostModel::new(1_000.0, 10.0, 0.001)
+			.with_instance_size(3 * GB);
+
+		let cost = model.cost(&curve(), GB, Interpolation::Step);
+
+		assert_eq!(cost.provisioned_size, 3 * GB);
+		assert_close(cost.memory_cost, 30.0);
+
+		let sizes = model
+			.costs(&curve(), Interpolation::Step)
+			.iter()
+			.map(|cost| cost.size)
+			.collect::<Vec<u64>>();
+
+		assert_eq!(sizes, vec![0, 3 * GB, 6 * GB]);
+	}
+
+	#[test]
+	fn cost_curve_is_relative() {
+		let model = CostModel::new(1_000.0, 10.0, 0.001);
+		let cost_curve = model.cost_curve(&curve(), Interpolation::Step);
+
+		assert_eq!(cost_curve.size(), 5);
+		assert!(cost_curve.into_iter().all(|point| point.get_miss_ratio() <= 1.0));
+		assert_eq!(cost_curve.get_miss_ratio(0), 1.0);
+	}
+}
@@ -6,25 +6,24 @@
  */
 
 use std::{
-	io,
+	io::{self, Error, ErrorKind},
+	str::FromStr,
 	collections::BTreeMap,
 	collections::btree_map::Values,
 	ops::Bound,
 	iter::IntoIterator,
 };
 
-use kwik::{
-	math,
-	file::{
-		FileReader,
-		FileWriter,
-		csv::{CsvWriter, CsvReader, RowData, ReadRow, WriteRow},
-	},
+use kwik::file::{
+	FileWriter,
+	csv::{CsvWriter, RowData, ReadRow, WriteRow},
 };
 
 use crate::{
 	histogram::Histogram,
 	shards::Shards,
+	curve_comparison::CurveComparison,
+	curve_set::CurveSet,
 };
 
 /// An MRC curve, storing the points of the MRC.
@@ -40,6 +39,21 @@ pub struct Point {
 	miss_ratio: f64,
 }
 
+/// The way in which the miss ratio between the points of an MRC is
+/// computed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Interpolation {
+	/// The miss ratio of the previous point (or `1.0` before the first
+	/// point).
+	#[default]
+	Step,
+
+	/// The miss ratio on the line between the previous and next points.
+	/// Before the first point, the line starts at a miss ratio of `1.0`
+	/// at size zero.
+	Linear,
+}
+
 impl Curve {
 	/// Returns an empty MRC.
 	pub fn new() -> Self {
@@ -48,6 +62,17 @@ impl Curve {
 		}
 	}
 
+	/// Returns an MRC with the supplied (size, miss ratio) points.
+	pub fn from_points(points: &[(u64, f64)]) -> Self {
+		let mut curve = Curve::new();
+
+		for (size, miss_ratio) in points {
+			curve.add(*size, *miss_ratio);
+		}
+
+		curve
+	}
+
 	/// Returns the number of points in the MRC.
 	pub fn size(&self) -> usize {
 		self.points.len()
@@ -158,6 +183,155 @@ impl Curve {
 		}
 	}
 
+	/// Returns the miss ratio at the supplied size, interpolated between
+	/// the points of the MRC using the supplied mode. Beyond the last
+	/// point, the miss ratio of the last point is returned.
+	pub fn get_interpolated_miss_ratio(&self, size: u64, interpolation: Interpolation) -> f64 {
+		if interpolation == Interpolation::Step {
+			return self.get_miss_ratio(size);
+		}
+
+		let prev = self.points
+			.upper_bound(Bound::Included(&size))
+			.prev()
+			.map(|(_, point)| point)
+			.cloned()
+			.unwrap_or(Point::new(0, 1.0));
+
+		let Some((_, next)) = self.points.lower_bound(Bound::Included(&size)).next() else {
+			return match self.points.last_key_value() {
+				Some((_, point)) => point.get_miss_ratio(),
+				None => 1.0,
+			};
+		};
+
+		if next.get_size() == prev.get_size() {
+			return next.get_miss_ratio();
+		}
+
+		let position = (size - prev.get_size()) as f64 /
+			(next.get_size() - prev.get_size()) as f64;
+
+		prev.get_miss_ratio() + position * (next.get_miss_ratio() - prev.get_miss_ratio())
+	}
+
+	/// Returns the lower convex hull of the MRC, whose points are the
+	/// vertices of the hull. As used by Talus, any point on the hull can be
+	/// achieved by partitioning the cache between the two neighbouring
+	/// vertices, so the hull should be read with linear interpolation.
+	/// Like linear interpolation, the hull starts at a miss ratio of `1.0`
+	/// at size zero if the MRC has no point there.
+	pub fn convex_hull(&self) -> Curve {
+		let origin = Point::new(0, 1.0);
+		let mut hull = Vec::<&Point>::new();
+
+		if !self.points.contains_key(&0) {
+			hull.push(&origin);
+		}
+
+		for point in self.points.values() {
+			while hull.len() >= 2 && !is_convex(hull[hull.len() - 2], hull[hull.len() - 1], point) {
+				hull.pop();
+			}
+
+			hull.push(point);
+		}
+
+		Curve {
+			points: hull
+				.into_iter()
+				.map(|point| (point.get_size(), point.clone()))
+				.collect(),
+		}
+	}
+
+	/// Returns the smallest size at which the miss ratio is at most the
+	/// supplied target, if the MRC reaches it. With step interpolation,
+	/// this is the size of a point of the MRC.
+	pub fn get_size_for_miss_ratio(&self, target: f64, interpolation: Interpolation) -> Option<u64> {
+		let mut prev = Point::new(0, 1.0);
+
+		if prev.get_miss_ratio() <= target {
+			return Some(0);
+		}
+
+		for point in self.points.values() {
+			if point.get_miss_ratio() <= target {
+				if interpolation == Interpolation::Step {
+					return Some(point.get_size());
+				}
+
+				// the previous miss ratio is above the target, so the line
+				// crosses it between the points
+				let position = (prev.get_miss_ratio() - target) /
+					(prev.get_miss_ratio() - point.get_miss_ratio());
+
+				let size = prev.get_size() as f64 +
+					position * (point.get_size() - prev.get_size()) as f64;
+
+				// sizes within rounding error of a whole number are not
+				// rounded up past it
+				let size = match (size - size.round()).abs() < 1e-9 {
+					true => size.round(),
+					false => size.ceil(),
+				};
+
+				return Some((size as u64).min(point.get_size()));
+			}
+
+			prev = point.clone();
+		}
+
+		None
+	}
+
+	/// Returns the size of the knee of the MRC (i.e., the point after
+	/// which extra cache yields diminishing returns). The knee is the point
+	/// furthest below the line between the first and last points, with
+	/// both axes normalized to the range of the MRC.
+	pub fn get_knee(&self) -> Option<u64> {
+		let (first, last) = (
+			self.points.first_key_value()?.1,
+			self.points.last_key_value()?.1,
+		);
+
+		let size_range = (last.get_size() - first.get_size()) as f64;
+		let miss_ratio_range = first.get_miss_ratio() - last.get_miss_ratio();
+
+		if size_range == 0.0 || miss_ratio_range <= 0.0 {
+			return None;
+		}
+
+		self.points
+			.values()
+			.map(|point| {
+				let x = (point.get_size() - first.get_size()) as f64 / size_range;
+				let y = (first.get_miss_ratio() - point.get_miss_ratio()) / miss_ratio_range;
+
+				(point.get_size(), y - x)
+			})
+			.filter(|(_, distance)| *distance > 0.0)
+			.max_by(|(_, distance), (_, other_distance)| distance.total_cmp(other_distance))
+			.map(|(size, _)| size)
+	}
+
+	/// Returns the decrease in miss ratio (i.e., the increase in hit ratio)
+	/// from adding the extra size to a cache of the supplied size.
+	pub fn get_marginal_hit_gain(
+		&self,
+		size: u64,
+		extra_size: u64,
+		interpolation: Interpolation,
+	) -> f64 {
+		self.get_interpolated_miss_ratio(size, interpolation) -
+			self.get_interpolated_miss_ratio(size + extra_size, interpolation)
+	}
+
+	/// Returns the point at exactly the supplied size, if it exists.
+	pub fn get_point(&self, size: u64) -> Option<&Point> {
+		self.points.get(&size)
+	}
+
 	/// Adds a point to the MRC.
 	pub fn add(&mut self, size: u64, miss_ratio: f64) {
 		self.points.insert(
@@ -167,25 +341,14 @@ impl Curve {
 	}
 
 	/// Returns the mean absolute error (MAE) of the current MRC
-	/// compared to the supplied MRC.
-	pub fn mae(&self, curve: &Curve) -> f64 {
-		let max_size = *math::max(&[
-			self.get_max_size(),
-			curve.get_max_size(),
-		]).unwrap();
-
-		let num_points: u64 = 100;
-		let step_size = max_size / num_points;
-
-		let total = (step_size..(max_size + step_size))
-			.step_by(step_size as usize)
-			.fold(0.0_f64, |total_error, size| {
-				total_error + (
-					self.get_miss_ratio(size) - curve.get_miss_ratio(size)
-				).abs()
-			});
-
-		total / num_points as f64
+	/// compared to the supplied MRC, where both are interpolated using
+	/// the supplied mode. Please refer to `CurveComparison` for other
+	/// metrics and sampling grids.
+	pub fn mae(&self, curve: &Curve, interpolation: Interpolation) -> f64 {
+		CurveComparison::new()
+			.with_interpolation(interpolation)
+			.compare(self, curve)
+			.mae
 	}
 
 	/// Saves the MRC to a CSV file.
@@ -199,16 +362,16 @@ impl Curve {
 		Ok(())
 	}
 
-	/// Constructs an MRC from a CSV file.
+	/// Constructs an MRC from a file. If the file stores several curves
+	/// (see `CurveSet`), the first curve is returned.
 	pub fn from_file(path: &str) -> io::Result<Curve> {
-		let mut curve = Curve::default();
-		let reader = CsvReader::<Point>::from_path(path)?;
-
-		for point in reader {
-			curve.add(point.get_size(), point.get_miss_ratio());
-		}
-
-		Ok(curve)
+		CurveSet::from_file(path)?
+			.first()
+			.cloned()
+			.ok_or(Error::new(
+				ErrorKind::InvalidData,
+				"The curve file does not contain a curve.",
+			))
 	}
 }
 
@@ -229,6 +392,22 @@ impl Point {
 	}
 }
 
+impl FromStr for Interpolation {
+	type Err = Error;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match value {
+			"step" => Ok(Interpolation::Step),
+			"linear" => Ok(Interpolation::Linear),
+
+			_ => Err(Error::new(
+				ErrorKind::InvalidData,
+				"Invalid interpolation (expected step or linear).",
+			)),
+		}
+	}
+}
+
 impl ReadRow for Point {
 	fn from_row(row: &RowData) -> io::Result<Self> {
 		let size = row.get(0)?
@@ -265,3 +444,91 @@ impl<'a> IntoIterator for &'a Curve {
 		self.points.values()
 	}
 }
+
+/// Returns `true` if the middle point lies strictly below the line between
+/// the first and last points (i.e., the three points turn counterclockwise).
+fn is_convex(first: &Point, middle: &Point, last: &Point) -> bool {
+	let cross =
+		(middle.get_size() as f64 - first.get_size() as f64) *
+			(last.get_miss_ratio() - first.get_miss_ratio()) -
+		(middle.get_miss_ratio() - first.get_miss_ratio()) *
+			(last.get_size() as f64 - first.get_size() as f64);
+
+	cross > 0.0
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		curve::{Curve, Interpolation},
+		test_utils::assert_close,
+	};
+
+	#[test]
+	fn linear_interpolation_between_points() {
+		let curve = Curve::from_points(&[(100, 0.8), (200, 0.4)]);
+
+		assert_close(curve.get_interpolated_miss_ratio(150, Interpolation::Step), 0.8);
+		assert_close(curve.get_interpolated_miss_ratio(150, Interpolation::Linear), 0.6);
+		assert_close(curve.get_interpolated_miss_ratio(200, Interpolation::Linear), 0.4);
+		assert_close(curve.get_interpolated_miss_ratio(300, Interpolation::Linear), 0.4);
+
+		// before the first point, the line starts at a miss ratio of 1.0
+		assert_close(curve.get_interpolated_miss_ratio(0, Interpolation::Linear), 1.0);
+		assert_close(curve.get_interpolated_miss_ratio(50, Interpolation::Linear), 0.9);
+	}
+
+	#[test]
+	fn convex_hull_removes_points_above_hull() {
+		let curve = Curve::from_points(&[
+			(100, 0.9),
+			(200, 0.85),
+			(300, 0.3),
+			(400, 0.28),
+			(500, 0.25),
+			(600, 0.1),
+		]);
+
+		let hull = curve.convex_hull();
+		let sizes = hull.into_iter().map(|point| point.get_size()).collect::<Vec<u64>>();
+
+		assert_eq!(sizes, vec![0, 300, 600]);
+
+		for point in &curve {
+			let hull_miss_ratio = hull.get_interpolated_miss_ratio(point.get_size(), Interpolation::Linear);
+			assert!(hull_miss_ratio <= point.get_miss_ratio() + 1e-12);
+		}
+	}
+
+	#[test]
+	fn size_for_miss_ratio_is_found() {
+		let curve = Curve::from_points(&[(100, 0.8), (200, 0.4), (300, 0.1)]);
+
+		assert_eq!(curve.get_size_for_miss_ratio(0.4, Interpolation::Step), Some(200));
+		assert_eq!(curve.get_size_for_miss_ratio(0.3, Interpolation::Step), Some(300));
+		assert_eq!(curve.get_size_for_miss_ratio(0.6, Interpolation::Linear), Some(150));
+		assert_eq!(curve.get_size_for_miss_ratio(0.05, Interpolation::Linear), None);
+		assert_eq!(curve.get_size_for_miss_ratio(1.0, Interpolation::Step), Some(0));
+	}
+
+	#[test]
+	fn knee_and_marginal_gain() {
+		let curve = Curve::from_points(&[(100, 0.9), (200, 0.3), (300, 0.25), (400, 0.2), (500, 0.15)]);
+
+		assert_eq!(curve.get_knee(), Some(200));
+		assert_close(curve.get_marginal_hit_gain(100, 100, Interpolation::Step), 0.6);
+		assert_close(curve.get_marginal_hit_gain(300, 100, Interpolation::Step), 0.05);
+
+		assert_eq!(Curve::from_points(&[(100, 0.5)]).get_knee(), None);
+	}
+
+	#[test]
+	fn mae_uses_interpolation() {
+		// both curves lie on the same line, but have different steps
+		let coarse = Curve::from_points(&[(0, 1.0), (10_000, 0.0)]);
+		let fine = Curve::from_points(&[(0, 1.0), (5_000, 0.5), (10_000, 0.0)]);
+
+		assert_close(coarse.mae(&fine, Interpolation::Step), 0.25);
+		assert_close(coarse.mae(&fine, Interpolation::Linear), 0.0);
+	}
+}
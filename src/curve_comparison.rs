@@ -0,0 +1,227 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use crate::{
+	curve::{Curve, Interpolation},
+	grid::SizeGrid,
+};
+
+/// Compares two MRCs at the sizes of a sampling grid, optionally
+/// restricted to a range of sizes and weighted toward some sizes.
+#[derive(Clone, Default)]
+pub struct CurveComparison {
+	grid: SizeGrid,
+	interpolation: Interpolation,
+	range: Option<(u64, u64)>,
+	weighting: Weighting,
+}
+
+/// The weight of the error at each sampled size.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Weighting {
+	/// Every size has the same weight.
+	#[default]
+	Uniform,
+
+	/// The weight of a size is inversely proportional to it, which
+	/// favours small caches.
+	InverseSize,
+}
+
+/// The errors between two MRCs.
+#[derive(Clone, Debug, Default)]
+pub struct CurveErrors {
+	/// The (weighted) mean absolute error.
+	pub mae: f64,
+
+	/// The (weighted) root mean squared error.
+	pub rmse: f64,
+
+	/// The maximum absolute error, and the size at which it occurs.
+	pub max_error: f64,
+	pub max_error_size: u64,
+
+	/// The area between the curves (in units of size times miss ratio),
+	/// integrated over the sampled sizes with the trapezoidal rule.
+	pub area: f64,
+
+	/// The number of sampled sizes.
+	pub num_points: usize,
+}
+
+impl CurveComparison {
+	/// Returns a comparison which samples the default (linear) grid up to
+	/// the larger maximum size of the curves, using step interpolation.
+	pub fn new() -> Self {
+		CurveComparison::default()
+	}
+
+	/// Sets the grid of sizes at which the curves are sampled.
+	pub fn with_grid(mut self, grid: SizeGrid) -> Self {
+		self.grid = grid;
+		self
+	}
+
+	/// Sets the interpolation between the points of the curves.
+	pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+		self.interpolation = interpolation;
+		self
+	}
+
+	/// Restricts the comparison to the sizes of the grid (computed up to
+	/// the maximum size) which lie in the supplied inclusive range.
+	pub fn with_range(mut self, min_size: u64, max_size: u64) -> Self {
+		assert!(min_size <= max_size, "The minimum size of the range must not exceed its maximum size.");
+
+		self.range = Some((min_size, max_size));
+		self
+	}
+
+	/// Sets the weight of the error at each sampled size.
+	pub fn with_weighting(mut self, weighting: Weighting) -> Self {
+		self.weighting = weighting;
+		self
+	}
+
+	/// Returns the sizes at which the supplied curves are compared.
+	pub fn sizes(&self, expected: &Curve, actual: &Curve) -> Vec<u64> {
+		let (min_size, max_size) = self.range.unwrap_or((
+			0,
+			expected.get_max_size().max(actual.get_max_size()),
+		));
+
+		self.grid
+			.sizes(max_size)
+			.into_iter()
+			.filter(|size| (min_size..=max_size).contains(size))
+			.collect()
+	}
+
+	/// Returns the errors of the actual curve compared to the
+	/// expected curve.
+	pub fn compare(&self, expected: &Curve, actual: &Curve) -> CurveErrors {
+		let sizes = self.sizes(expected, actual);
+
+		if sizes.is_empty() {
+			return CurveErrors::default();
+		}
+
+		let errors = sizes
+			.iter()
+			.map(|size| (
+				expected.get_interpolated_miss_ratio(*size, self.interpolation) -
+					actual.get_interpolated_miss_ratio(*size, self.interpolation)
+			).abs())
+			.collect::<Vec<f64>>();
+
+		let mut total_weight: f64 = 0.0;
+		let mut total_error: f64 = 0.0;
+		let mut total_squared_error: f64 = 0.0;
+
+		let mut result = CurveErrors {
+			num_points: sizes.len(),
+			..CurveErrors::default()
+		};
+
+		for (index, (size, error)) in sizes.iter().zip(&errors).enumerate() {
+			let weight = self.weighting.weight(*size);
+
+			total_weight += weight;
+			total_error += weight * error;
+			total_squared_error += weight * error * error;
+
+			if *error > result.max_error || index == 0 {
+				result.max_error = *error;
+				result.max_error_size = *size;
+			}
+
+			if index > 0 {
+				let width = (size - sizes[index - 1]) as f64;
+				result.area += width * (error + errors[index - 1]) / 2.0;
+			}
+		}
+
+		result.mae = total_error / total_weight;
+		result.rmse = (total_squared_error / total_weight).sqrt();
+
+		result
+	}
+}
+
+impl Weighting {
+	fn weight(&self, size: u64) -> f64 {
+		match self {
+			Weighting::Uniform => 1.0,
+			Weighting::InverseSize => 1.0 / size.max(1) as f64,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		curve::{Curve, Interpolation},
+		curve_comparison::{CurveComparison, Weighting},
+		grid::SizeGrid,
+		test_utils::assert_close,
+	};
+
+	#[test]
+	fn small_curves_are_compared() {
+		// fewer sizes than grid points previously produced a step of zero
+		let expected = Curve::from_points(&[(10, 0.5), (50, 0.2)]);
+		let actual = Curve::from_points(&[(10, 0.5), (50, 0.2)]);
+
+		let errors = CurveComparison::new().compare(&expected, &actual);
+
+		assert_eq!(errors.num_points, 50);
+		assert_eq!(errors.mae, 0.0);
+		assert_eq!(expected.mae(&actual, Interpolation::Step), 0.0);
+	}
+
+	#[test]
+	fn errors_are_computed() {
+		let expected = Curve::from_points(&[(100, 0.5), (200, 0.5), (300, 0.5), (400, 0.5)]);
+		let actual = Curve::from_points(&[(100, 0.5), (200, 0.3), (300, 0.4), (400, 0.5)]);
+
+		let errors = CurveComparison::new()
+			.with_grid(SizeGrid::Explicit(vec![100, 200, 300, 400]))
+			.compare(&expected, &actual);
+
+		assert_close(errors.mae, 0.3 / 4.0);
+		assert_close(errors.rmse, (0.05_f64 / 4.0).sqrt());
+		assert_close(errors.max_error, 0.2);
+		assert_eq!(errors.max_error_size, 200);
+		assert_close(errors.area, 100.0 * (0.1 + 0.15 + 0.05));
+	}
+
+	#[test]
+	fn range_and_weighting_are_applied() {
+		let expected = Curve::from_points(&[(100, 0.5), (1_000, 0.5)]);
+		let actual = Curve::from_points(&[(100, 0.4), (1_000, 0.5)]);
+
+		let comparison = CurveComparison::new()
+			.with_grid(SizeGrid::Explicit(vec![100, 1_000]));
+
+		let ranged = comparison
+			.clone()
+			.with_range(500, 1_000)
+			.compare(&expected, &actual);
+
+		assert_eq!(ranged.num_points, 1);
+		assert_eq!(ranged.mae, 0.0);
+
+		let uniform = comparison.compare(&expected, &actual);
+
+		let weighted = comparison
+			.with_weighting(Weighting::InverseSize)
+			.compare(&expected, &actual);
+
+		assert_close(uniform.mae, 0.05);
+		assert!(weighted.mae > uniform.mae);
+	}
+}
@@ -5,19 +5,71 @@
  * LICENSE file in the root directory of this source tree.
  */
 
-use kwik::plot::{
-	Plot,
-	AxisFormat,
-	line_plot::{LinePlot, Line},
+use std::{
+	io::{Error, ErrorKind},
+	str::FromStr,
 };
 
-use crate::curve::Curve;
+use gnuplot::{
+	AxesCommon,
+	AutoOption::{Auto, Fix},
+	Caption,
+	Color,
+	ColorType,
+	TickOption,
+};
+
+use crate::{
+	curve::{Curve, Interpolation},
+	grid::SizeGrid,
+};
+
+const NUM_POINTS: u32 = 100;
+
+// the ratio of the largest to the smallest size of a log-scale x axis if
+// the smallest size is not set
+const LOG_RANGE: u64 = 10_000;
+
+// the colours of the lines, in the order of the curves (with the reference
+// first), so that each curve has the same colour in the error subplot
+const COLORS: [&str; 8] = [
+	"#9400d3",
+	"#009e73",
+	"#56b4e9",
+	"#e69f00",
+	"#f0e442",
+	"#0072b2",
+	"#e51e10",
+	"#000000",
+];
+
+const WIDTH_PX: u32 = 800;
+const HEIGHT_PX: u32 = 600;
+const WIDTH_IN: f32 = 6.0;
+const HEIGHT_IN: f32 = 4.5;
 
 /// A plot with multiple MRC curves.
 #[derive(Default)]
 pub struct CurvePlot {
 	curves: Vec<Curve>,
 	labels: Vec<Option<String>>,
+
+	// the curve (e.g., the accurate MRC) against which the errors of the
+	// other curves are plotted
+	reference: Option<(Curve, String)>,
+
+	x_scale: AxisScale,
+	x_range: Option<(u64, u64)>,
+	y_range: Option<(f64, f64)>,
+	error_plot: bool,
+}
+
+/// The scale of the x axis of a plot.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AxisScale {
+	#[default]
+	Linear,
+	Log,
 }
 
 impl CurvePlot {
@@ -25,8 +77,9 @@ impl CurvePlot {
 	pub fn get_max_size(&self) -> u64 {
 		self.curves
 			.iter()
-			.max_by_key(|curve| curve.get_max_size())
+			.chain(self.reference.as_ref().map(|(curve, _)| curve))
 			.map(|curve| curve.get_max_size())
+			.max()
 			.unwrap_or(0)
 	}
 
@@ -36,38 +89,284 @@ impl CurvePlot {
 		self.labels.push(label.map(|label| label.to_owned()));
 	}
 
-	/// Converts the curves to a line plot.
-	pub fn to_plot(&self) -> LinePlot {
-		let max_size = self.get_max_size();
-		let step_size = max_size / max_size.clamp(1, 100);
+	/// Sets the reference MRC (e.g., the accurate MRC), which is plotted
+	/// along with the other curves, and against which their errors are
+	/// plotted if the error plot is enabled.
+	pub fn set_reference(&mut self, curve: Curve, label: &str) {
+		self.reference = Some((curve, label.to_owned()));
+	}
+
+	/// Sets the scale of the x axis.
+	pub fn set_x_scale(&mut self, x_scale: AxisScale) {
+		self.x_scale = x_scale;
+	}
+
+	/// Sets the range of sizes of the x axis (by default, from zero, or the
+	/// maximum size / 10000 for a log scale, to the maximum size). Returns
+	/// an error if the minimum is not less than the maximum.
+	pub fn set_x_range(&mut self, min_size: u64, max_size: u64) -> Result<(), Error> {
+		if min_size >= max_size {
+			return Err(Error::new(
+				ErrorKind::InvalidInput,
+				"The x axis minimum must be less than its maximum.",
+			));
+		}
+
+		self.x_range = Some((min_size, max_size));
+		Ok(())
+	}
+
+	/// Sets the range of miss ratios of the y axis (by default, from zero
+	/// to one). Returns an error if the minimum is not less than the
+	/// maximum.
+	pub fn set_y_range(&mut self, min_miss_ratio: f64, max_miss_ratio: f64) -> Result<(), Error> {
+		if min_miss_ratio.is_nan() || max_miss_ratio.is_nan() || min_miss_ratio >= max_miss_ratio {
+			return Err(Error::new(
+				ErrorKind::InvalidInput,
+				"The y axis minimum must be less than its maximum.",
+			));
+		}
+
+		self.y_range = Some((min_miss_ratio, max_miss_ratio));
+		Ok(())
+	}
+
+	/// Enables a subplot of the absolute error of each curve compared to
+	/// the reference, if one is set.
+	pub fn set_error_plot(&mut self, error_plot: bool) {
+		self.error_plot = error_plot;
+	}
+
+	/// Saves the plot, with the error subplot if it is enabled and a
+	/// reference is set. The plot is saved as SVG or PNG if the path ends
+	/// in `.svg` or `.png`, and as PDF otherwise.
+	pub fn save(&self, path: &str, interpolation: Interpolation) -> Result<(), Error> {
+		let format = path
+			.rsplit_once('.')
+			.map(|(_, extension)| extension.to_lowercase())
+			.unwrap_or_default();
 
-		let mut plot = LinePlot::default()
-			.with_x_label("Size")
-			.with_y_label("Miss ratio")
-			.with_x_max(max_size)
-			.with_y_min(0)
-			.with_y_max(1)
-			.with_y_tick(0.2)
-			.with_x_format(AxisFormat::Memory);
+		let sizes = self.sizes();
+		let xs = sizes.iter().map(|size| *size as f64).collect::<Vec<f64>>();
 
-		for (index, curve) in self.curves.iter().enumerate() {
+		let error_plot = self.error_plot && self.reference.is_some();
+
+		let mut figure = gnuplot::Figure::new();
+
+		if error_plot {
+			figure.set_multiplot_layout(2, 1);
+		}
+
+		let (y_min, y_max) = self.y_range.unwrap_or((0.0, 1.0));
+
+		let axes = figure.axes2d();
+		self.configure_x_axis(axes, &sizes);
+
+		axes
+			.set_y_label("Miss ratio", &[])
+			.set_y_range(Fix(y_min), Fix(y_max));
+
+		for (index, (curve, label)) in self.labeled_curves().enumerate() {
 			if curve.is_empty() {
 				continue;
 			}
 
-			let mut line = Line::default();
+			let ys = sizes
+				.iter()
+				.map(|size| curve.get_interpolated_miss_ratio(*size, interpolation))
+				.collect::<Vec<f64>>();
 
-			if let Some(label) = &self.labels[index] {
-				line.set_label(label);
-			}
+			axes.lines(xs.iter().copied(), ys, &[Caption(label.unwrap_or("")), line_color(index)]);
+		}
+
+		if let Some((reference, reference_label)) = self.reference.as_ref().filter(|_| error_plot) {
+			let axes = figure.axes2d();
+			self.configure_x_axis(axes, &sizes);
+
+			axes
+				.set_y_label(&format!("Absolute error (vs. {reference_label})"), &[])
+				.set_y_range(Fix(0.0), Auto);
 
-			for size in (0..=max_size).step_by(step_size as usize) {
-				line.push(size, curve.get_miss_ratio(size));
+			// the reference is the first of the labeled curves
+			for (index, (curve, label)) in self.curves.iter().zip(&self.labels).enumerate() {
+				if curve.is_empty() {
+					continue;
+				}
+
+				let ys = sizes
+					.iter()
+					.map(|size| (
+						curve.get_interpolated_miss_ratio(*size, interpolation) -
+							reference.get_interpolated_miss_ratio(*size, interpolation)
+					).abs())
+					.collect::<Vec<f64>>();
+
+				axes.lines(xs.iter().copied(), ys, &[Caption(label.as_deref().unwrap_or("")), line_color(index + 1)]);
 			}
+		}
 
-			plot.line(line);
+		let result = match format.as_str() {
+			"svg" => figure.save_to_svg(path, WIDTH_PX, HEIGHT_PX),
+			"png" => figure.save_to_png(path, WIDTH_PX, HEIGHT_PX),
+			_ => figure.save_to_pdf(path, WIDTH_IN, HEIGHT_IN),
+		};
+
+		result
+			.map(|_| ())
+			.map_err(Error::other)
+	}
+
+	/// Returns the reference (if set) followed by the other curves, along
+	/// with their labels.
+	fn labeled_curves(&self) -> impl Iterator<Item = (&Curve, Option<&str>)> {
+		self.reference
+			.iter()
+			.map(|(curve, label)| (curve, Some(label.as_str())))
+			.chain(self.curves
+				.iter()
+				.zip(&self.labels)
+				.map(|(curve, label)| (curve, label.as_deref())))
+	}
+
+	/// Returns the sizes at which the curves are plotted, which are spaced
+	/// evenly on the scale of the x axis.
+	fn sizes(&self) -> Vec<u64> {
+		let (min_size, max_size) = self.x_range.unwrap_or((0, self.get_max_size()));
+
+		match self.x_scale {
+			AxisScale::Linear => {
+				let step_size = ((max_size - min_size) / NUM_POINTS as u64).max(1);
+
+				let mut sizes = (min_size..=max_size)
+					.step_by(step_size as usize)
+					.collect::<Vec<u64>>();
+
+				// the range may not be a multiple of the step
+				if sizes.last() != Some(&max_size) {
+					sizes.push(max_size);
+				}
+
+				sizes
+			},
+
+			AxisScale::Log => {
+				let min_size = match self.x_range {
+					Some(_) => min_size.max(1),
+					None => (max_size / LOG_RANGE).max(1),
+				};
+
+				SizeGrid::Log(NUM_POINTS, Some(min_size)).sizes(max_size)
+			},
+		}
+	}
+
+	fn configure_x_axis(&self, axes: &mut gnuplot::Axes2D, sizes: &[u64]) {
+		let min_size = sizes.first().copied().unwrap_or(0) as f64;
+		let max_size = sizes.last().copied().unwrap_or(0) as f64;
+
+		axes
+			.set_x_label("Size", &[])
+			.set_x_ticks(Some((Auto, 0)), &[TickOption::Format("%.0b%BB")], &[]);
+
+		if max_size > min_size {
+			axes.set_x_range(Fix(min_size), Fix(max_size));
+		}
+
+		if self.x_scale == AxisScale::Log {
+			axes.set_x_log(Some(2.0));
+		}
+	}
+}
+
+fn line_color(index: usize) -> gnuplot::PlotOption<&'static str> {
+	Color(ColorType::RGBString(COLORS[index % COLORS.len()]))
+}
+
+impl FromStr for AxisScale {
+	type Err = Error;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match value {
+			"linear" => Ok(AxisScale::Linear),
+			"log" => Ok(AxisScale::Log),
+
+			_ => Err(Error::new(
+				ErrorKind::InvalidData,
+				"Invalid axis scale (expected linear or log).",
+			)),
 		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		curve::Curve,
+		curve_plot::{CurvePlot, AxisScale},
+	};
+
+	fn curve_plot() -> CurvePlot {
+		let mut curve = Curve::new();
+		curve.add(1_000_000, 0.5);
+
+		let mut curve_plot = CurvePlot::default();
+		curve_plot.add(curve, Some("Kosmo"));
+
+		curve_plot
+	}
+
+	#[test]
+	fn linear_sizes_cover_range() {
+		let mut curve_plot = curve_plot();
+
+		let sizes = curve_plot.sizes();
+		assert_eq!(sizes.first(), Some(&0));
+		assert_eq!(sizes.last(), Some(&1_000_000));
+		assert_eq!(sizes.len(), 101);
+
+		curve_plot.set_x_range(500_000, 600_000).unwrap();
+
+		let sizes = curve_plot.sizes();
+		assert_eq!(sizes.first(), Some(&500_000));
+		assert_eq!(sizes.last(), Some(&600_000));
+
+		// the range is not a multiple of the step of 1,234
+		curve_plot.set_x_range(0, 123_456).unwrap();
+
+		let sizes = curve_plot.sizes();
+		assert_eq!(sizes.first(), Some(&0));
+		assert_eq!(sizes[100], 123_400);
+		assert_eq!(sizes.last(), Some(&123_456));
+		assert_eq!(sizes.len(), 102);
+	}
+
+	#[test]
+	fn log_sizes_are_positive() {
+		let mut curve_plot = curve_plot();
+		curve_plot.set_x_scale(AxisScale::Log);
+
+		let sizes = curve_plot.sizes();
+		assert_eq!(sizes.first(), Some(&100));
+		assert_eq!(sizes.last(), Some(&1_000_000));
+
+		let mut reference = Curve::new();
+		reference.add(2_000_000, 0.25);
+		curve_plot.set_reference(reference, "Accurate");
+
+		assert_eq!(curve_plot.get_max_size(), 2_000_000);
+		assert_eq!(curve_plot.labeled_curves().count(), 2);
+	}
+
+	#[test]
+	fn empty_ranges_are_rejected() {
+		let mut curve_plot = curve_plot();
+
+		assert!(curve_plot.set_x_range(5, 5).is_err());
+		assert!(curve_plot.set_x_range(6, 5).is_err());
+		assert!(curve_plot.set_y_range(0.5, 0.2).is_err());
+		assert!(curve_plot.set_y_range(f64::NAN, 1.0).is_err());
 
-		plot
+		assert_eq!(curve_plot.x_range, None);
+		assert_eq!(curve_plot.y_range, None);
 	}
 }
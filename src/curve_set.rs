@@ -0,0 +1,472 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::{
+	fs::{self, File},
+	io::{BufWriter, Error, ErrorKind, Write},
+	path::Path,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use kwik::file::{
+	FileReader,
+	csv::CsvReader,
+};
+
+use serde::{Serialize, Deserialize};
+
+use crate::curve::{Curve, Point};
+
+// the name of the curve loaded from a (single-curve) CSV file without
+// a header
+const LEGACY_CURVE_NAME: &str = "curve";
+
+/// Several named MRCs, along with the metadata of the run which
+/// produced them.
+///
+/// File formats (chosen by the extension of the path when saving):
+/// - `.json`: the metadata and the points of each curve.
+/// - otherwise, a wide CSV: the metadata as `# <key>=<value>` lines, a
+///   header of `size` followed by the curve names, and one row per size
+///   with an empty cell for each curve without a point at that size.
+#[derive(Clone, Default)]
+pub struct CurveSet {
+	pub metadata: CurveMetadata,
+	curves: Vec<(String, Curve)>,
+}
+
+/// The configuration of the run which produced a set of curves.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CurveMetadata {
+	pub trace_path: Option<String>,
+	pub algorithm: Option<String>,
+	pub policy: Option<String>,
+	pub shards_t: Option<u64>,
+	pub shards_s_max: Option<u32>,
+	pub granularity: Option<u32>,
+
+	/// The time at which the curves were produced (in seconds since the
+	/// Unix epoch).
+	pub timestamp: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CurveSetRecord {
+	metadata: CurveMetadata,
+	curves: Vec<CurveRecord>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CurveRecord {
+	name: String,
+	points: Vec<(u64, f64)>,
+}
+
+impl CurveSet {
+	/// Returns an empty set of curves with the supplied metadata.
+	pub fn new(metadata: CurveMetadata) -> Self {
+		CurveSet {
+			metadata,
+			curves: Vec::new(),
+		}
+	}
+
+	/// Adds a named curve to the set, replacing any curve with the
+	/// same name.
+	pub fn add(&mut self, name: &str, curve: Curve) {
+		match self.curves.iter_mut().find(|(curve_name, _)| curve_name == name) {
+			Some((_, existing)) => *existing = curve,
+			None => self.curves.push((name.to_string(), curve)),
+		}
+	}
+
+	/// Returns the curve with the supplied name, if it exists.
+	pub fn get(&self, name: &str) -> Option<&Curve> {
+		self.curves
+			.iter()
+			.find(|(curve_name, _)| curve_name == name)
+			.map(|(_, curve)| curve)
+	}
+
+	/// Returns the named curves in the order in which they were added.
+	pub fn curves(&self) -> &[(String, Curve)] {
+		&self.curves
+	}
+
+	/// Returns the first curve of the set, if any.
+	pub fn first(&self) -> Option<&Curve> {
+		self.curves.first().map(|(_, curve)| curve)
+	}
+
+	/// Saves the curves and metadata as JSON if the path ends in `.json`,
+	/// and as a wide CSV otherwise.
+	pub fn to_file(&self, path: &str) -> Result<(), Error> {
+		match path.ends_with(".json") {
+			true => self.to_json(path),
+			false => self.to_csv(path),
+		}
+	}
+
+	/// Saves the curves and metadata as JSON.
+	pub fn to_json(&self, path: &str) -> Result<(), Error> {
+		let record = CurveSetRecord {
+			metadata: self.metadata.clone(),
+			curves: self.curves
+				.iter()
+				.map(|(name, curve)| CurveRecord {
+					name: name.clone(),
+					points: curve
+						.into_iter()
+						.map(|point| (point.get_size(), point.get_miss_ratio()))
+						.collect(),
+				})
+				.collect(),
+		};
+
+		let writer = BufWriter::new(File::create(path)?);
+		serde_json::to_writer_pretty(writer, &record)?;
+
+		Ok(())
+	}
+
+	/// Saves the curves and metadata as a wide CSV. Returns an error if a
+	/// curve name contains a comma or a newline, which cannot be stored in
+	/// the header.
+	pub fn to_csv(&self, path: &str) -> Result<(), Error> {
+		if self.curves.iter().any(|(name, _)| name.contains(',') || name.contains('\n')) {
+			return Err(invalid_csv("Curve names cannot contain commas or newlines in a CSV file."));
+		}
+
+		let mut writer = BufWriter::new(File::create(path)?);
+
+		for (key, value) in self.metadata.to_pairs() {
+			writeln!(writer, "# {key}={value}")?;
+		}
+
+		let names = self.curves
+			.iter()
+			.map(|(name, _)| name.as_str())
+			.collect::<Vec<&str>>();
+
+		writeln!(writer, "size,{}", names.join(","))?;
+
+		let mut sizes = self.curves
+			.iter()
+			.flat_map(|(_, curve)| curve.into_iter().map(|point| point.get_size()))
+			.collect::<Vec<u64>>();
+
+		sizes.sort_unstable();
+		sizes.dedup();
+
+		for size in sizes {
+			let cells = self.curves
+				.iter()
+				.map(|(_, curve)| curve
+					.get_point(size)
+					.map(|point| point.get_miss_ratio().to_string())
+					.unwrap_or_default())
+				.collect::<Vec<String>>();
+
+			writeln!(writer, "{size},{}", cells.join(","))?;
+		}
+
+		writer.flush()
+	}
+
+	/// Loads a set of curves from a JSON file, a wide CSV file, or a
+	/// (single-curve) CSV file without a header, as saved by
+	/// `Curve::to_file`.
+	pub fn from_file(path: &str) -> Result<CurveSet, Error> {
+		let contents = fs::read_to_string(path)?;
+
+		let first_line = contents
+			.lines()
+			.map(str::trim)
+			.find(|line| !line.is_empty())
+			.unwrap_or_default();
+
+		if first_line.starts_with('{') {
+			return CurveSet::from_json(&contents);
+		}
+
+		if first_line.starts_with('#') || first_line.starts_with("size") {
+			return CurveSet::from_csv(&contents);
+		}
+
+		let mut curve = Curve::new();
+		let reader = CsvReader::<Point>::from_path(path)?;
+
+		for point in reader {
+			curve.add(point.get_size(), point.get_miss_ratio());
+		}
+
+		let mut curve_set = CurveSet::default();
+		curve_set.add(LEGACY_CURVE_NAME, curve);
+
+		Ok(curve_set)
+	}
+
+	fn from_json(contents: &str) -> Result<CurveSet, Error> {
+		let record = serde_json::from_str::<CurveSetRecord>(contents)?;
+		let mut curve_set = CurveSet::new(record.metadata);
+
+		for curve_record in record.curves {
+			let mut curve = Curve::new();
+
+			for (size, miss_ratio) in curve_record.points {
+				curve.add(size, miss_ratio);
+			}
+
+			curve_set.add(&curve_record.name, curve);
+		}
+
+		Ok(curve_set)
+	}
+
+	fn from_csv(contents: &str) -> Result<CurveSet, Error> {
+		let mut curve_set = CurveSet::default();
+		let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+
+		let header = loop {
+			let Some(line) = lines.next() else {
+				return Err(invalid_csv("The curve file has no header."));
+			};
+
+			match line.strip_prefix('#') {
+				Some(pair) => curve_set.metadata.set_pair(pair.trim())?,
+				None => break line,
+			}
+		};
+
+		let names = header
+			.split(',')
+			.skip(1)
+			.collect::<Vec<&str>>();
+
+		let mut curves = names
+			.iter()
+			.map(|_| Curve::new())
+			.collect::<Vec<Curve>>();
+
+		for line in lines {
+			let cells = line.split(',').collect::<Vec<&str>>();
+
+			if cells.len() != names.len() + 1 {
+				return Err(invalid_csv("Invalid number of cells in curve file row."));
+			}
+
+			let Ok(size) = cells[0].trim().parse::<u64>() else {
+				return Err(invalid_csv("Invalid size in curve file."));
+			};
+
+			for (curve, cell) in curves.iter_mut().zip(&cells[1..]) {
+				let cell = cell.trim();
+
+				if cell.is_empty() {
+					continue;
+				}
+
+				let Ok(miss_ratio) = cell.parse::<f64>() else {
+					return Err(invalid_csv("Invalid miss ratio in curve file."));
+				};
+
+				curve.add(size, miss_ratio);
+			}
+		}
+
+		for (name, curve) in names.into_iter().zip(curves) {
+			curve_set.add(name.trim(), curve);
+		}
+
+		Ok(curve_set)
+	}
+}
+
+impl CurveMetadata {
+	/// Returns empty metadata with the current time as its timestamp.
+	pub fn new() -> Self {
+		let timestamp = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map(|duration| duration.as_secs())
+			.ok();
+
+		CurveMetadata {
+			timestamp,
+			..CurveMetadata::default()
+		}
+	}
+
+	fn to_pairs(&self) -> Vec<(&'static str, String)> {
+		let mut pairs = Vec::<(&'static str, String)>::new();
+
+		let mut push = |key: &'static str, value: Option<String>| {
+			if let Some(value) = value {
+				pairs.push((key, value));
+			}
+		};
+
+		push("trace_path", self.trace_path.clone());
+		push("algorithm", self.algorithm.clone());
+		push("policy", self.policy.clone());
+		push("shards_t", self.shards_t.map(|value| value.to_string()));
+		push("shards_s_max", self.shards_s_max.map(|value| value.to_string()));
+		push("granularity", self.granularity.map(|value| value.to_string()));
+		push("timestamp", self.timestamp.map(|value| value.to_string()));
+
+		pairs
+	}
+
+	fn set_pair(&mut self, pair: &str) -> Result<(), Error> {
+		let Some((key, value)) = pair.split_once('=') else {
+			return Err(invalid_csv("Invalid metadata in curve file."));
+		};
+
+		let value = value.to_string();
+
+		match key {
+			"trace_path" => self.trace_path = Some(value),
+			"algorithm" => self.algorithm = Some(value),
+			"policy" => self.policy = Some(value),
+			"shards_t" => self.shards_t = Some(parse_metadata(&value)?),
+			"shards_s_max" => self.shards_s_max = Some(parse_metadata(&value)?),
+			"granularity" => self.granularity = Some(parse_metadata(&value)?),
+			"timestamp" => self.timestamp = Some(parse_metadata(&value)?),
+
+			// unknown keys are ignored, so that newer files can be read
+			_ => {},
+		}
+
+		Ok(())
+	}
+}
+
+fn parse_metadata<T: std::str::FromStr>(value: &str) -> Result<T, Error> {
+	value
+		.parse::<T>()
+		.map_err(|_| invalid_csv("Invalid metadata value in curve file."))
+}
+
+/// Returns the label of a curve file (its name without the extension),
+/// which names its curve in plots and reports.
+pub fn get_file_label(path: &str) -> String {
+	Path::new(path)
+		.file_stem()
+		.map(|stem| stem.to_string_lossy().to_string())
+		.unwrap_or(path.to_string())
+}
+
+fn invalid_csv(message: &str) -> Error {
+	Error::new(ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		curve::{Curve, Interpolation},
+		curve_set::{CurveSet, CurveMetadata},
+	};
+
+	fn curve_set() -> CurveSet {
+		let metadata = CurveMetadata {
+			trace_path: Some("/path/to/trace.bin".to_string()),
+			algorithm: Some("Kosmo".to_string()),
+			policy: Some("lru,fifo".to_string()),
+			shards_t: Some(1677721),
+			shards_s_max: Some(2048),
+			granularity: Some(10),
+			..CurveMetadata::new()
+		};
+
+		let mut lru = Curve::new();
+		lru.add(100, 0.75);
+		lru.add(200, 0.5);
+
+		let mut fifo = Curve::new();
+		fifo.add(100, 0.8);
+		fifo.add(300, 0.25);
+
+		let mut curve_set = CurveSet::new(metadata);
+		curve_set.add("lru", lru);
+		curve_set.add("fifo", fifo);
+
+		curve_set
+	}
+
+	fn check_round_trip(extension: &str) {
+		let path = std::env::temp_dir()
+			.join(format!("kosmo-curve-set-{}.{extension}", std::process::id()))
+			.to_string_lossy()
+			.to_string();
+
+		let expected = curve_set();
+		expected.to_file(&path).unwrap();
+
+		let actual = CurveSet::from_file(&path).unwrap();
+
+		assert_eq!(actual.metadata, expected.metadata);
+		assert_eq!(actual.curves().len(), 2);
+
+		for (name, curve) in expected.curves() {
+			let loaded = actual.get(name).unwrap();
+
+			assert_eq!(loaded.size(), curve.size());
+			assert_eq!(loaded.mae(curve, Interpolation::Step), 0.0);
+		}
+
+		// a single curve is loaded as the first curve of the set
+		let first = Curve::from_file(&path).unwrap();
+		assert_eq!(first.mae(expected.get("lru").unwrap(), Interpolation::Step), 0.0);
+
+		std::fs::remove_file(&path).unwrap();
+	}
+
+	#[test]
+	fn json_round_trip() {
+		check_round_trip("json");
+	}
+
+	#[test]
+	fn csv_round_trip() {
+		check_round_trip("csv");
+	}
+
+	#[test]
+	fn legacy_csv_is_loaded() {
+		let path = std::env::temp_dir()
+			.join(format!("kosmo-curve-set-legacy-{}.csv", std::process::id()))
+			.to_string_lossy()
+			.to_string();
+
+		let mut curve = Curve::new();
+		curve.add(100, 0.5);
+		curve.add(200, 0.25);
+		curve.to_file(&path).unwrap();
+
+		let curve_set = CurveSet::from_file(&path).unwrap();
+
+		assert_eq!(curve_set.metadata, CurveMetadata::default());
+		assert_eq!(curve_set.first().unwrap().size(), 2);
+
+		std::fs::remove_file(&path).unwrap();
+	}
+
+	#[test]
+	fn csv_rejects_names_with_commas() {
+		let path = std::env::temp_dir()
+			.join(format!("kosmo-curve-set-comma-{}", std::process::id()))
+			.to_string_lossy()
+			.to_string();
+
+		let mut curve_set = CurveSet::default();
+		curve_set.add("2q-0.25,0.5", Curve::from_points(&[(100, 0.5)]));
+
+		assert!(curve_set.to_csv(&format!("{path}.csv")).is_err());
+
+		curve_set.to_json(&format!("{path}.json")).unwrap();
+		std::fs::remove_file(format!("{path}.json")).unwrap();
+	}
+}
@@ -0,0 +1,241 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::{
+	fs,
+	io::{Error, ErrorKind},
+	str::FromStr,
+};
+
+const DEFAULT_NUM_POINTS: u32 = 100;
+
+// the ratio of the largest to the smallest size of a log-spaced grid
+// if the smallest size is not specified
+const DEFAULT_LOG_RANGE: u64 = 10_000;
+
+/// The cache sizes at which an MRC is computed. The same grid spec
+/// produces the same sizes in every tool, so their curves line up point
+/// for point.
+///
+/// Spec formats:
+/// - `linear[-<points>]`: the sizes i * maximum size / points (rounded
+///   down) for i from 1 to the number of points, which end at the maximum
+///   size. A maximum size below the number of points has fewer sizes.
+/// - `log[-<points>[-<min size>]]`: log-spaced sizes from the minimum
+///   size (the maximum size / 10000 by default) up to the maximum size.
+/// - `<path>`: a file with one size per line.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SizeGrid {
+	Linear(u32),
+	Log(u32, Option<u64>),
+	Explicit(Vec<u64>),
+}
+
+impl SizeGrid {
+	/// Returns the ascending, deduplicated cache sizes of the grid. The
+	/// maximum size is ignored by explicit grids.
+	pub fn sizes(&self, max_size: u64) -> Vec<u64> {
+		let mut sizes = match self {
+			SizeGrid::Linear(num_points) => {
+				let num_points = *num_points as u64;
+
+				(1..=num_points)
+					.map(|index| index * max_size / num_points)
+					.filter(|size| *size > 0)
+					.collect::<Vec<u64>>()
+			},
+
+			SizeGrid::Log(num_points, min_size) => {
+				let min_size = min_size
+					.unwrap_or(max_size / DEFAULT_LOG_RANGE)
+					.clamp(1, max_size.max(1));
+
+				log_sizes(*num_points, min_size, max_size)
+			},
+
+			SizeGrid::Explicit(sizes) => sizes.clone(),
+		};
+
+		sizes.sort_unstable();
+		sizes.dedup();
+
+		sizes
+	}
+}
+
+impl Default for SizeGrid {
+	fn default() -> Self {
+		SizeGrid::Linear(DEFAULT_NUM_POINTS)
+	}
+}
+
+impl FromStr for SizeGrid {
+	type Err = Error;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		match value {
+			"linear" => Ok(SizeGrid::Linear(DEFAULT_NUM_POINTS)),
+			"log" => Ok(SizeGrid::Log(DEFAULT_NUM_POINTS, None)),
+
+			value if value.starts_with("linear-") => parse_linear_config(value),
+			value if value.starts_with("log-") => parse_log_config(value),
+
+			path => parse_grid_file(path),
+		}
+	}
+}
+
+fn log_sizes(num_points: u32, min_size: u64, max_size: u64) -> Vec<u64> {
+	if num_points <= 1 {
+		return vec![max_size];
+	}
+
+	let ratio = (max_size as f64 / min_size as f64)
+		.powf(1.0 / (num_points - 1) as f64);
+
+	(0..num_points)
+		.map(|index| match index + 1 == num_points {
+			// avoid rounding errors at the largest size
+			true => max_size,
+			false => (min_size as f64 * ratio.powi(index as i32)).round() as u64,
+		})
+		.collect::<Vec<u64>>()
+}
+
+fn parse_num_points(value: &str) -> Result<u32, Error> {
+	match value.parse::<u32>() {
+		Ok(num_points) if num_points > 0 => Ok(num_points),
+
+		_ => Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid size grid number of points.",
+		)),
+	}
+}
+
+fn parse_linear_config(value: &str) -> Result<SizeGrid, Error> {
+	let num_points = parse_num_points(&value.replace("linear-", ""))?;
+
+	Ok(SizeGrid::Linear(num_points))
+}
+
+fn parse_log_config(value: &str) -> Result<SizeGrid, Error> {
+	let replaced = value.replace("log-", "");
+
+	let values = replaced
+		.split('-')
+		.collect::<Vec<&str>>();
+
+	if values.len() > 2 {
+		return Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid log size grid config.",
+		));
+	}
+
+	let num_points = parse_num_points(values[0])?;
+
+	let min_size = match values.get(1) {
+		Some(value) => match value.parse::<u64>() {
+			Ok(min_size) if min_size > 0 => Some(min_size),
+
+			_ => return Err(Error::new(
+				ErrorKind::InvalidData,
+				"Invalid log size grid minimum size.",
+			)),
+		},
+
+		None => None,
+	};
+
+	Ok(SizeGrid::Log(num_points, min_size))
+}
+
+fn parse_grid_file(path: &str) -> Result<SizeGrid, Error> {
+	let Ok(contents) = fs::read_to_string(path) else {
+		return Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid size grid (expected linear, log, or a path to a file of sizes).",
+		));
+	};
+
+	let sizes = contents
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty() && !line.starts_with('#'))
+		.map(|line| line.parse::<u64>())
+		.collect::<Result<Vec<u64>, _>>()
+		.map_err(|_| Error::new(
+			ErrorKind::InvalidData,
+			"Invalid size in size grid file.",
+		))?;
+
+	if sizes.is_empty() || sizes.contains(&0) {
+		return Err(Error::new(
+			ErrorKind::InvalidData,
+			"The size grid file must contain at least one size, and sizes must be positive.",
+		));
+	}
+
+	Ok(SizeGrid::Explicit(sizes))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use crate::grid::SizeGrid;
+
+	#[test]
+	fn linear_grid_has_one_size_per_point() {
+		let sizes = SizeGrid::default().sizes(3_374_336);
+
+		assert_eq!(sizes.len(), 100);
+		assert_eq!(sizes[0], 33_743);
+		assert_eq!(sizes[99], 3_374_336);
+
+		let sizes = SizeGrid::Linear(100).sizes(2_050);
+
+		assert_eq!(sizes.len(), 100);
+		assert_eq!(sizes[0], 20);
+		assert_eq!(sizes[99], 2_050);
+
+		assert_eq!(SizeGrid::Linear(3).sizes(10), vec![3, 6, 10]);
+
+		// sizes of zero are dropped and duplicates are removed
+		assert_eq!(SizeGrid::Linear(100).sizes(10), (1..=10).collect::<Vec<u64>>());
+	}
+
+	#[test]
+	fn log_grid_is_log_spaced() {
+		let sizes = SizeGrid::from_str("log-5-10").unwrap().sizes(100_000);
+
+		assert_eq!(sizes, vec![10, 100, 1_000, 10_000, 100_000]);
+		assert_eq!(SizeGrid::from_str("log").unwrap().sizes(1_000_000)[0], 100);
+	}
+
+	#[test]
+	fn file_grid_is_sorted_and_deduplicated() {
+		let path = std::env::temp_dir()
+			.join(format!("kosmo-grid-{}", std::process::id()));
+
+		std::fs::write(&path, "# sizes\n300\n100\n\n200\n100\n").unwrap();
+
+		let grid = SizeGrid::from_str(path.to_str().unwrap()).unwrap();
+		assert_eq!(grid.sizes(0), vec![100, 200, 300]);
+
+		std::fs::remove_file(&path).unwrap();
+	}
+
+	#[test]
+	fn invalid_specs_are_rejected() {
+		assert!(SizeGrid::from_str("linear-0").is_err());
+		assert!(SizeGrid::from_str("log-10-0").is_err());
+		assert!(SizeGrid::from_str("log-10-5-5").is_err());
+		assert!(SizeGrid::from_str("/does/not/exist").is_err());
+	}
+}
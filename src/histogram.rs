@@ -6,11 +6,15 @@
  */
 
 use std::iter::IntoIterator;
+
+use serde::{Serialize, Deserialize};
+
 use crate::shards::Shards;
 
 pub const BUCKET_SIZE: u64 = 64 * 1024;
 
 /// A histogram of stack distances.
+#[derive(Serialize, Deserialize)]
 pub struct Histogram {
 	infinity: Bucket,
 	buckets: Vec<Bucket>,
@@ -18,6 +22,7 @@ pub struct Histogram {
 
 /// A bucket of the histogram, reducing the granularity of
 /// counters to save memory.
+#[derive(Serialize, Deserialize)]
 pub struct Bucket {
 	size: u64,
 	count: f64,
@@ -31,7 +36,7 @@ impl Histogram {
 		let shards_global_t = shards.map(|shards| shards.get_global_t());
 
 		Histogram {
-			infinity: Bucket::new(0, shards_global_t),
+			infinity: Bucket::empty(0, shards_global_t),
 			buckets: Vec::new(),
 		}
 	}
@@ -145,6 +150,15 @@ impl Bucket {
 		}
 	}
 
+	/// Constructs a bucket with a count of zero (e.g., the infinity bucket,
+	/// which exists before any access is counted).
+	pub fn empty(size: u64, shards_global_t: Option<u64>) -> Self {
+		let mut bucket = Bucket::new(size, shards_global_t);
+		bucket.count = 0.0;
+
+		bucket
+	}
+
 	pub fn get_size(&self) -> u64 {
 		self.size
 	}
@@ -154,7 +168,7 @@ impl Bucket {
 	}
 
 	pub fn clear(&mut self) {
-		self.count = 1.0;
+		self.count = 0.0;
 	}
 
 	pub fn increment(&mut self) {
@@ -185,3 +199,21 @@ impl<'a> IntoIterator for &'a Histogram {
 fn get_rounded_reuse_distance(reuse_distance: u64) -> u64 {
 	(reuse_distance as f64 / BUCKET_SIZE as f64).ceil() as u64 * BUCKET_SIZE
 }
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn infinity_bucket_starts_empty() {
+		use crate::histogram::{Histogram, BUCKET_SIZE};
+
+		let mut histogram = Histogram::new(None);
+		assert_eq!(histogram.get_total(), 0.0);
+
+		histogram.increment(None, None);
+		histogram.increment(None, Some(BUCKET_SIZE));
+		assert_eq!(histogram.get_total(), 2.0);
+
+		histogram.clear();
+		assert_eq!(histogram.get_total(), 0.0);
+	}
+}
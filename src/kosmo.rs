@@ -11,6 +11,9 @@ mod eviction_map;
 mod local_object;
 mod reconstructed_stack;
 mod evictions;
+mod plugin;
+
+use std::io::{Error, ErrorKind, Read, Write};
 
 use rustc_hash::FxHashMap;
 use rayon::prelude::*;
@@ -18,21 +21,34 @@ use kwik::math;
 
 use crate::{
 	access::{Access, Key},
-	algorithm::Algorithm,
+	algorithm::{self, Algorithm},
 	histogram::Histogram,
 	shards::Shards,
 	curve::Curve,
+	checkpoint,
 	kosmo::{
-		global_object::GlobalObject,
 		reconstructed_stack::ReconstructedStackPolicy,
 		evictions::Evictions,
-		eviction_map::EvictionMap,
 	},
 };
 
-pub use crate::kosmo::policy::KosmoPolicy;
+pub use crate::kosmo::{
+	policy::KosmoPolicy,
+	global_object::GlobalObject,
+	eviction_map::EvictionMap,
+	local_object::LocalObject,
+	reconstructed_stack::ReconstructedStack,
+	plugin::{
+		KosmoPlugin,
+		KosmoPluginPolicy,
+		DynEvictionMap,
+		DynReconstructedStack,
+		register_plugin,
+		find_plugin,
+	},
+};
 
-const GRANULARITY: u32 = 10;
+pub const GRANULARITY: u32 = 10;
 const MIN_RECONSTRUCTED_STACK_SIZE: u64 = 1024;
 
 // The Kosmo MRC generation algorithm.
@@ -85,6 +101,14 @@ impl Algorithm for Kosmo {
 		self.policy_curve(&policy).unwrap_or_default()
 	}
 
+	fn curves(&mut self) -> Vec<Curve> {
+		self.policies
+			.clone()
+			.iter()
+			.map(|policy| self.policy_curve(policy).unwrap_or_default())
+			.collect()
+	}
+
 	fn verify_shards(&mut self, access: &Access) -> bool {
 		if let Some(ref mut shards) = self.shards {
 			if !shards.sample(access) {
@@ -98,6 +122,37 @@ impl Algorithm for Kosmo {
 
 		true
 	}
+
+	fn save(&self, writer: &mut dyn Write) -> Result<(), Error> {
+		if self.policies.iter().any(|policy| matches!(policy, KosmoPolicy::Plugin(_))) {
+			return Err(Error::new(
+				ErrorKind::Unsupported,
+				"Kosmo plugin policies do not support checkpoints.",
+			));
+		}
+
+		checkpoint::save(writer, &format!("{:?}", self.policies))?;
+		checkpoint::save(writer, &self.total_size)?;
+		checkpoint::save(writer, &self.global_table)?;
+		checkpoint::save(writer, &self.histograms)?;
+
+		checkpoint::save_shards(writer, self.shards.as_deref())
+	}
+
+	fn restore(&mut self, reader: &mut dyn Read) -> Result<(), Error> {
+		if checkpoint::restore::<String>(reader)? != format!("{:?}", self.policies) {
+			return Err(Error::new(
+				ErrorKind::InvalidData,
+				"The checkpoint was saved with different Kosmo policies.",
+			));
+		}
+
+		self.total_size = checkpoint::restore(reader)?;
+		self.global_table = checkpoint::restore(reader)?;
+		self.histograms = checkpoint::restore(reader)?;
+
+		checkpoint::restore_shards(reader, &mut self.shards)
+	}
 }
 
 impl Kosmo {
@@ -106,7 +161,7 @@ impl Kosmo {
 		shards: Option<Box<dyn Shards>>,
 	) -> Self {
 		assert!(!policies.is_empty(), "Kosmo must be configured with at least one policy.");
-		assert!(!has_duplicate_policies(policies), "Kosmo cannot have duplicate policies.");
+		assert!(!algorithm::has_duplicates(policies), "Kosmo cannot have duplicate policies.");
 
 		let histograms = policies
 			.iter()
@@ -125,6 +180,13 @@ impl Kosmo {
 		}
 	}
 
+	/// Sets the number of cache sizes at which the policy stacks are
+	/// reconstructed for each access (higher is more accurate).
+	pub fn set_granularity(&mut self, granularity: u32) {
+		assert!(granularity > 0);
+		self.granularity = granularity;
+	}
+
 	/// Returns the MRC of the supplied policy, if it exists.
 	pub fn policy_curve(&mut self, policy: &KosmoPolicy) -> Option<Curve> {
 		let policy_index = find_policy_index(&self.policies, policy)?;
@@ -226,7 +288,7 @@ impl Kosmo {
 
 		for global_object in global_table.values() {
 			for (stack, eviction_map) in stacks.iter_mut().zip(global_object.eviction_maps()) {
-				stack.insert(eviction_map.as_local_object(global_object, size));
+				stack.insert_from(eviction_map, global_object, size);
 			}
 		}
 
@@ -234,7 +296,7 @@ impl Kosmo {
 	}
 }
 
-fn init_reconstructed_stacks(policies: &[KosmoPolicy], size: u64) -> Vec<ReconstructedStackPolicy> {
+fn init_reconstructed_stacks(policies: &[KosmoPolicy], size: u64) -> Vec<ReconstructedStackPolicy<'_>> {
 	policies
 		.iter()
 		.map(|policy| ReconstructedStackPolicy::new(policy, size))
@@ -250,15 +312,3 @@ fn find_policy_index(policies: &[KosmoPolicy], policy: &KosmoPolicy) -> Option<u
 
 	None
 }
-
-fn has_duplicate_policies(policies: &[KosmoPolicy]) -> bool {
-	for (index, policy) in policies.iter().enumerate().take(policies.len() - 1) {
-		for other in policies.iter().skip(index + 1) {
-			if policy.eq(other) {
-				return true;
-			}
-		}
-	}
-
-	false
-}
@@ -10,10 +10,19 @@ mod fifo_eviction_map;
 mod two_q_eviction_map;
 mod lrfu_eviction_map;
 mod lru_eviction_map;
+mod sieve_eviction_map;
+mod s3_fifo_eviction_map;
+mod lirs_eviction_map;
+mod gds_eviction_map;
+mod lru_k_eviction_map;
+mod slru_eviction_map;
+
+use serde::{Serialize, Deserialize};
 
 use crate::{
 	access::Access,
 	algorithm::Object,
+	kosmo::plugin,
 };
 
 pub use crate::kosmo::{
@@ -24,12 +33,20 @@ pub use crate::kosmo::{
 		two_q_eviction_map::TwoQEvictionMap,
 		lrfu_eviction_map::LrfuEvictionMap,
 		lru_eviction_map::LruEvictionMap,
+		sieve_eviction_map::SieveEvictionMap,
+		s3_fifo_eviction_map::S3FifoEvictionMap,
+		lirs_eviction_map::LirsEvictionMap,
+		gds_eviction_map::GdsEvictionMap,
+		lru_k_eviction_map::LruKEvictionMap,
+		slru_eviction_map::SlruEvictionMap,
 	},
 	global_object::GlobalObject,
 	local_object::LocalObjectPolicy,
 };
 
 pub trait EvictionMap {
+	type LocalObject<'a>;
+
 	fn insert(&mut self, _: u64);
 
 	fn exists_at(&self, _: u64) -> bool;
@@ -37,15 +54,26 @@ pub trait EvictionMap {
 
 	fn update(&mut self, _: &Access) {}
 
-	fn as_local_object<'a>(&self, _: &'a GlobalObject, _: u64) -> LocalObjectPolicy<'a>;
+	fn as_local_object<'a>(&self, _: &'a GlobalObject, _: u64) -> Self::LocalObject<'a>;
 }
 
+#[derive(Serialize, Deserialize)]
 pub enum EvictionMapPolicy {
 	Lfu(LfuEvictionMap),
 	Fifo(FifoEvictionMap),
 	TwoQ(TwoQEvictionMap),
 	Lrfu(LrfuEvictionMap),
 	Lru(LruEvictionMap),
+	Sieve(SieveEvictionMap),
+	S3Fifo(S3FifoEvictionMap),
+	Lirs(LirsEvictionMap),
+	Gds(GdsEvictionMap),
+	LruK(LruKEvictionMap),
+	Slru(SlruEvictionMap),
+
+	// plugin eviction maps are opaque, so they cannot be checkpointed
+	#[serde(skip)]
+	Plugin(Box<dyn plugin::DynEvictionMap>),
 }
 
 impl EvictionMapPolicy {
@@ -70,11 +98,41 @@ impl EvictionMapPolicy {
 			KosmoPolicy::Lru => EvictionMapPolicy::Lru(
 				LruEvictionMap::new(access)
 			),
+
+			KosmoPolicy::Sieve => EvictionMapPolicy::Sieve(
+				SieveEvictionMap::new(access)
+			),
+
+			KosmoPolicy::S3Fifo(..) => EvictionMapPolicy::S3Fifo(
+				S3FifoEvictionMap::new(access)
+			),
+
+			KosmoPolicy::Lirs(_) => EvictionMapPolicy::Lirs(
+				LirsEvictionMap::new(access)
+			),
+
+			KosmoPolicy::Gds(_) | KosmoPolicy::Gdsf(_) => EvictionMapPolicy::Gds(
+				GdsEvictionMap::new()
+			),
+
+			KosmoPolicy::LruK(k, correlated_period) => EvictionMapPolicy::LruK(
+				LruKEvictionMap::new(access, *k, *correlated_period)
+			),
+
+			KosmoPolicy::Slru(ratios) => EvictionMapPolicy::Slru(
+				SlruEvictionMap::new(access, ratios.len())
+			),
+
+			KosmoPolicy::Plugin(plugin) => EvictionMapPolicy::Plugin(
+				plugin.new_eviction_map(access)
+			),
 		}
 	}
 }
 
 impl EvictionMap for EvictionMapPolicy {
+	type LocalObject<'a> = LocalObjectPolicy<'a>;
+
 	fn insert(&mut self, size: u64) {
 		match self {
 			EvictionMapPolicy::Lfu(eviction_map) => eviction_map.insert(size),
@@ -82,6 +140,13 @@ impl EvictionMap for EvictionMapPolicy {
 			EvictionMapPolicy::TwoQ(eviction_map) => eviction_map.insert(size),
 			EvictionMapPolicy::Lrfu(eviction_map) => eviction_map.insert(size),
 			EvictionMapPolicy::Lru(eviction_map) => eviction_map.insert(size),
+			EvictionMapPolicy::Sieve(eviction_map) => eviction_map.insert(size),
+			EvictionMapPolicy::S3Fifo(eviction_map) => eviction_map.insert(size),
+			EvictionMapPolicy::Lirs(eviction_map) => eviction_map.insert(size),
+			EvictionMapPolicy::Gds(eviction_map) => eviction_map.insert(size),
+			EvictionMapPolicy::LruK(eviction_map) => eviction_map.insert(size),
+			EvictionMapPolicy::Slru(eviction_map) => eviction_map.insert(size),
+			EvictionMapPolicy::Plugin(eviction_map) => eviction_map.insert(size),
 		}
 	}
 
@@ -92,6 +157,13 @@ impl EvictionMap for EvictionMapPolicy {
 			EvictionMapPolicy::TwoQ(eviction_map) => eviction_map.exists_at(size),
 			EvictionMapPolicy::Lrfu(eviction_map) => eviction_map.exists_at(size),
 			EvictionMapPolicy::Lru(eviction_map) => eviction_map.exists_at(size),
+			EvictionMapPolicy::Sieve(eviction_map) => eviction_map.exists_at(size),
+			EvictionMapPolicy::S3Fifo(eviction_map) => eviction_map.exists_at(size),
+			EvictionMapPolicy::Lirs(eviction_map) => eviction_map.exists_at(size),
+			EvictionMapPolicy::Gds(eviction_map) => eviction_map.exists_at(size),
+			EvictionMapPolicy::LruK(eviction_map) => eviction_map.exists_at(size),
+			EvictionMapPolicy::Slru(eviction_map) => eviction_map.exists_at(size),
+			EvictionMapPolicy::Plugin(eviction_map) => eviction_map.exists_at(size),
 		}
 	}
 
@@ -102,6 +174,13 @@ impl EvictionMap for EvictionMapPolicy {
 			EvictionMapPolicy::TwoQ(eviction_map) => eviction_map.reuse_distance(object),
 			EvictionMapPolicy::Lrfu(eviction_map) => eviction_map.reuse_distance(object),
 			EvictionMapPolicy::Lru(eviction_map) => eviction_map.reuse_distance(object),
+			EvictionMapPolicy::Sieve(eviction_map) => eviction_map.reuse_distance(object),
+			EvictionMapPolicy::S3Fifo(eviction_map) => eviction_map.reuse_distance(object),
+			EvictionMapPolicy::Lirs(eviction_map) => eviction_map.reuse_distance(object),
+			EvictionMapPolicy::Gds(eviction_map) => eviction_map.reuse_distance(object),
+			EvictionMapPolicy::LruK(eviction_map) => eviction_map.reuse_distance(object),
+			EvictionMapPolicy::Slru(eviction_map) => eviction_map.reuse_distance(object),
+			EvictionMapPolicy::Plugin(eviction_map) => eviction_map.reuse_distance(object),
 		}
 	}
 
@@ -112,6 +191,13 @@ impl EvictionMap for EvictionMapPolicy {
 			EvictionMapPolicy::TwoQ(eviction_map) => eviction_map.update(access),
 			EvictionMapPolicy::Lrfu(eviction_map) => eviction_map.update(access),
 			EvictionMapPolicy::Lru(eviction_map) => eviction_map.update(access),
+			EvictionMapPolicy::Sieve(eviction_map) => eviction_map.update(access),
+			EvictionMapPolicy::S3Fifo(eviction_map) => eviction_map.update(access),
+			EvictionMapPolicy::Lirs(eviction_map) => eviction_map.update(access),
+			EvictionMapPolicy::Gds(eviction_map) => eviction_map.update(access),
+			EvictionMapPolicy::LruK(eviction_map) => eviction_map.update(access),
+			EvictionMapPolicy::Slru(eviction_map) => eviction_map.update(access),
+			EvictionMapPolicy::Plugin(eviction_map) => eviction_map.update(access),
 		}
 	}
 
@@ -135,6 +221,27 @@ impl EvictionMap for EvictionMapPolicy {
 
 			EvictionMapPolicy::Lru(eviction_map) =>
 				eviction_map.as_local_object(global_object, cache_size),
+
+			EvictionMapPolicy::Sieve(eviction_map) =>
+				eviction_map.as_local_object(global_object, cache_size),
+
+			EvictionMapPolicy::S3Fifo(eviction_map) =>
+				eviction_map.as_local_object(global_object, cache_size),
+
+			EvictionMapPolicy::Lirs(eviction_map) =>
+				eviction_map.as_local_object(global_object, cache_size),
+
+			EvictionMapPolicy::Gds(eviction_map) =>
+				eviction_map.as_local_object(global_object, cache_size),
+
+			EvictionMapPolicy::LruK(eviction_map) =>
+				eviction_map.as_local_object(global_object, cache_size),
+
+			EvictionMapPolicy::Slru(eviction_map) =>
+				eviction_map.as_local_object(global_object, cache_size),
+
+			EvictionMapPolicy::Plugin(_) =>
+				panic!("Plugin eviction maps are inserted into their reconstructed stacks directly."),
 		}
 	}
 }
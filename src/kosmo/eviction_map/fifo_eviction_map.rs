@@ -7,6 +7,8 @@
 
 use std::cmp;
 
+use serde::{Serialize, Deserialize};
+
 use crate::{
 	access::{Access, Timestamp},
 	algorithm::Object,
@@ -17,33 +19,35 @@ use crate::{
 	},
 };
 
+#[derive(Serialize, Deserialize)]
 pub struct FifoEvictionMap {
 	map: Vec<EvictionRecord>,
 }
 
+#[derive(Serialize, Deserialize)]
 struct EvictionRecord {
 	size: u64,
 	timestamp: Timestamp,
 }
 
 impl EvictionMap for FifoEvictionMap {
+	type LocalObject<'a> = LocalObjectPolicy<'a>;
+
 	fn insert(&mut self, size: u64) {
 		if self.map.last().is_some_and(|record| record.size > size) {
 			return;
 		}
 
+		// the timestamp at the sizes above the eviction is that of the
+		// largest record which is removed
 		let mut updated_timestamp: Timestamp = 0;
 
-		if self.map.last().is_some_and(|record| record.size <= size) {
+		while self.map.last().is_some_and(|record| record.size <= size) {
 			if let Some(record) = self.map.pop() {
 				updated_timestamp = record.timestamp;
 			}
 		}
 
-		while self.map.last().is_some_and(|record| record.size <= size) {
-			self.map.pop();
-		}
-
 		let should_insert = match self.map.last() {
 			Some(record) => record.size != size + 1,
 			None => true,
@@ -162,4 +166,32 @@ mod tests {
 		assert_eq!(eviction_map.timestamp_at(5), Some(3));
 		assert_eq!(eviction_map.timestamp_at(6), Some(1));
 	}
+
+	#[test]
+	fn eviction_keeps_timestamp_of_largest_record() {
+		use crate::access::{Access, Command};
+		use crate::kosmo::eviction_map::{EvictionMap, FifoEvictionMap};
+
+		let mut access = Access {
+			timestamp: 1,
+			command: Command::Get,
+			key: 0,
+			size: 1,
+			ttl: None,
+		};
+
+		let mut eviction_map = FifoEvictionMap::new(&access);
+		eviction_map.insert(5);
+
+		access.timestamp += 1;
+		eviction_map.update(&access);
+		assert_eq!(eviction_map.timestamp_at(5), Some(2));
+		assert_eq!(eviction_map.timestamp_at(6), Some(1));
+
+		// both records are removed, and the sizes above the eviction keep
+		// the original insertion of the object
+		eviction_map.insert(10);
+		assert_eq!(eviction_map.timestamp_at(10), None);
+		assert_eq!(eviction_map.timestamp_at(11), Some(1));
+	}
 }
@@ -0,0 +1,67 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use serde::{Serialize, Deserialize};
+
+use crate::{
+	access::Access,
+	algorithm::Object,
+	kosmo::{
+		eviction_map::{EvictionMap, LfuEvictionMap},
+		global_object::GlobalObject,
+		local_object::{LocalObjectPolicy, GdsLocalObject},
+	},
+};
+
+/// A GreedyDual-Size object's residency and frequency (i.e., the number
+/// of accesses since it was last inserted) at each cache size are tracked
+/// in the same way as an LFU object's.
+#[derive(Serialize, Deserialize)]
+pub struct GdsEvictionMap {
+	frequency_map: LfuEvictionMap,
+}
+
+impl EvictionMap for GdsEvictionMap {
+	type LocalObject<'a> = LocalObjectPolicy<'a>;
+
+	fn insert(&mut self, size: u64) {
+		self.frequency_map.insert(size);
+	}
+
+	fn exists_at(&self, size: u64) -> bool {
+		self.frequency_map.exists_at(size)
+	}
+
+	fn reuse_distance(&self, object: &Object) -> u64 {
+		self.frequency_map.reuse_distance(object)
+	}
+
+	fn update(&mut self, access: &Access) {
+		self.frequency_map.update(access);
+	}
+
+	fn as_local_object<'a>(
+		&self,
+		global_object: &'a GlobalObject,
+		cache_size: u64,
+	) -> LocalObjectPolicy<'a> {
+		let local_object = GdsLocalObject::new(
+			global_object,
+			self.frequency_map.count_at(cache_size),
+		);
+
+		LocalObjectPolicy::Gds(local_object)
+	}
+}
+
+impl GdsEvictionMap {
+	pub fn new() -> Self {
+		GdsEvictionMap {
+			frequency_map: LfuEvictionMap::new(),
+		}
+	}
+}
@@ -5,6 +5,8 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use serde::{Serialize, Deserialize};
+
 use crate::{
 	access::Access,
 	algorithm::Object,
@@ -15,17 +17,21 @@ use crate::{
 	},
 };
 
+#[derive(Serialize, Deserialize)]
 pub struct LfuEvictionMap {
 	global_count: u64,
 	map: Vec<EvictionRecord>,
 }
 
+#[derive(Serialize, Deserialize)]
 struct EvictionRecord {
 	size: u64,
 	count: u64,
 }
 
 impl EvictionMap for LfuEvictionMap {
+	type LocalObject<'a> = LocalObjectPolicy<'a>;
+
 	fn insert(&mut self, size: u64) {
 		while self.map.last().is_some_and(|record| record.size <= size) {
 			self.map.pop();
@@ -39,7 +45,9 @@ impl EvictionMap for LfuEvictionMap {
 	}
 
 	fn reuse_distance(&self, object: &Object) -> u64 {
-		for record in self.map.iter().rev() {
+		// the object may have been evicted at several sizes since its last
+		// access, so the largest of those sizes is used
+		for record in self.map.iter() {
 			if record.count == self.global_count {
 				return record.size + 1;
 			}
@@ -128,4 +136,32 @@ mod tests {
 		assert_eq!(eviction_map.count_at(5), Some(1));
 		assert_eq!(eviction_map.count_at(6), Some(3));
 	}
+
+	#[test]
+	fn reuse_distance_is_correct() {
+		use crate::access::{Access, Command};
+		use crate::algorithm::Object;
+		use crate::kosmo::eviction_map::{EvictionMap, LfuEvictionMap};
+
+		let access = Access {
+			timestamp: 0,
+			command: Command::Get,
+			key: 0,
+			size: 1,
+			ttl: None,
+		};
+
+		let object = Object::new(&access);
+
+		let mut eviction_map = LfuEvictionMap::new();
+		assert_eq!(eviction_map.reuse_distance(&object), 1);
+
+		eviction_map.update(&access);
+
+		// the object is evicted at two sizes since its last access, so it
+		// only exists above the larger of them
+		eviction_map.insert(5);
+		eviction_map.insert(3);
+		assert_eq!(eviction_map.reuse_distance(&object), 6);
+	}
 }
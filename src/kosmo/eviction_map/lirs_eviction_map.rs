@@ -0,0 +1,214 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::cmp;
+
+use serde::{Serialize, Deserialize};
+
+use crate::{
+	access::Access,
+	algorithm::Object,
+	kosmo::{
+		eviction_map::EvictionMap,
+		global_object::GlobalObject,
+		local_object::{
+			LocalObjectPolicy,
+			LirsLocalObject,
+			lirs_local_object::LirsStatus,
+		},
+	},
+};
+
+/// The records of a LIRS eviction map cover every cache size, since an
+/// object which is not resident in a cache may still be in its LIRS stack
+/// as a non-resident HIR object. A resident HIR object which is accessed
+/// is assumed to still be in the LIRS stack, and so becomes a LIR object.
+#[derive(Serialize, Deserialize)]
+pub struct LirsEvictionMap {
+	map: Vec<EvictionRecord>,
+	last_evicted_size: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EvictionRecord {
+	size: u64,
+	status: Option<LirsStatus>,
+	evicted: bool,
+}
+
+impl EvictionMap for LirsEvictionMap {
+	type LocalObject<'a> = LocalObjectPolicy<'a>;
+
+	/// Evictions of an object are performed from the largest cache size
+	/// down, so an object which became a non-resident HIR object due to
+	/// a larger eviction is not pruned by the smaller evictions which
+	/// follow it.
+	fn insert(&mut self, size: u64) {
+		if self.last_evicted_size.is_none_or(|last_evicted_size| size >= last_evicted_size) {
+			self.map.iter_mut().for_each(|record| record.evicted = false);
+		}
+
+		self.last_evicted_size = Some(size);
+
+		let Some(index) = self.map.iter().position(|record| record.size <= size + 1) else {
+			return;
+		};
+
+		if self.map[index].size <= size {
+			let status = self.map[index].status;
+			self.map.insert(index, EvictionRecord::new(size + 1, status));
+		}
+
+		for record in self.map.iter_mut().filter(|record| record.size <= size) {
+			match record.status {
+				Some(LirsStatus::ResidentHir(_)) => {
+					record.status = Some(LirsStatus::NonResidentHir);
+					record.evicted = true;
+				},
+
+				Some(LirsStatus::NonResidentHir) if record.evicted => {},
+				_ => record.status = None,
+			}
+		}
+
+		self.coalesce();
+	}
+
+	fn exists_at(&self, size: u64) -> bool {
+		is_resident(self.status_at(size))
+	}
+
+	fn reuse_distance(&self, object: &Object) -> u64 {
+		let smallest_resident = self.map
+			.iter()
+			.rev()
+			.find(|record| is_resident(record.status))
+			.map(|record| record.size);
+
+		match smallest_resident {
+			Some(size) => cmp::max(size, object.size as u64),
+			None => object.size as u64,
+		}
+	}
+
+	fn update(&mut self, access: &Access) {
+		self.last_evicted_size = None;
+
+		for record in self.map.iter_mut() {
+			record.evicted = false;
+
+			record.status = match record.status {
+				Some(_) => Some(LirsStatus::Lir),
+				None => Some(LirsStatus::ResidentHir(access.timestamp)),
+			};
+		}
+
+		self.coalesce();
+	}
+
+	fn as_local_object<'a>(
+		&self,
+		global_object: &'a GlobalObject,
+		cache_size: u64,
+	) -> LocalObjectPolicy<'a> {
+		let local_object = LirsLocalObject::new(
+			global_object,
+			self.status_at(cache_size),
+		);
+
+		LocalObjectPolicy::Lirs(local_object)
+	}
+}
+
+impl LirsEvictionMap {
+	pub fn new(access: &Access) -> Self {
+		LirsEvictionMap {
+			map: vec![EvictionRecord::new(0, Some(LirsStatus::ResidentHir(access.timestamp)))],
+			last_evicted_size: None,
+		}
+	}
+
+	pub fn status_at(&self, size: u64) -> Option<LirsStatus> {
+		self.map
+			.iter()
+			.find(|record| record.size <= size)
+			.and_then(|record| record.status)
+	}
+
+	/// Merges neighbouring records which share a status.
+	fn coalesce(&mut self) {
+		self.map.dedup_by(|record, previous| {
+			if record.status != previous.status || record.evicted != previous.evicted {
+				return false;
+			}
+
+			previous.size = record.size;
+			true
+		});
+	}
+}
+
+impl EvictionRecord {
+	fn new(size: u64, status: Option<LirsStatus>) -> Self {
+		EvictionRecord {
+			size,
+			status,
+			evicted: false,
+		}
+	}
+}
+
+fn is_resident(status: Option<LirsStatus>) -> bool {
+	matches!(
+		status,
+		Some(LirsStatus::Lir) | Some(LirsStatus::ResidentHir(_)),
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn status_is_correct() {
+		use crate::{
+			access::{Access, Command},
+			kosmo::{
+				eviction_map::{EvictionMap, LirsEvictionMap},
+				local_object::lirs_local_object::LirsStatus,
+			},
+		};
+
+		let mut access = Access {
+			timestamp: 1,
+			command: Command::Get,
+			key: 0,
+			size: 1,
+			ttl: None,
+		};
+
+		let mut eviction_map = LirsEvictionMap::new(&access);
+		assert_eq!(eviction_map.status_at(100), Some(LirsStatus::ResidentHir(1)));
+
+		eviction_map.insert(50);
+		assert_eq!(eviction_map.status_at(50), Some(LirsStatus::NonResidentHir));
+		assert_eq!(eviction_map.status_at(51), Some(LirsStatus::ResidentHir(1)));
+
+		access.timestamp += 1;
+		eviction_map.update(&access);
+		assert_eq!(eviction_map.status_at(50), Some(LirsStatus::Lir));
+		assert_eq!(eviction_map.status_at(51), Some(LirsStatus::Lir));
+
+		eviction_map.insert(20);
+		assert_eq!(eviction_map.status_at(20), None);
+		assert!(!eviction_map.exists_at(20));
+		assert!(eviction_map.exists_at(21));
+
+		access.timestamp += 1;
+		eviction_map.update(&access);
+		assert_eq!(eviction_map.status_at(20), Some(LirsStatus::ResidentHir(3)));
+		assert_eq!(eviction_map.status_at(100), Some(LirsStatus::Lir));
+	}
+}
@@ -7,6 +7,8 @@
 
 use std::cmp;
 
+use serde::{Serialize, Deserialize};
+
 use crate::{
 	access::{Access, Timestamp},
 	algorithm::Object,
@@ -17,6 +19,7 @@ use crate::{
 	},
 };
 
+#[derive(Serialize, Deserialize)]
 pub struct LrfuEvictionMap {
 	p: f64,
 	lambda: f64,
@@ -25,12 +28,15 @@ pub struct LrfuEvictionMap {
 	map: Vec<EvictionRecord>,
 }
 
+#[derive(Serialize, Deserialize)]
 struct EvictionRecord {
 	size: u64,
 	crf: f64,
 }
 
 impl EvictionMap for LrfuEvictionMap {
+	type LocalObject<'a> = LocalObjectPolicy<'a>;
+
 	fn insert(&mut self, size: u64) {
 		if self.map.last().is_some_and(|record| record.size > size) {
 			return;
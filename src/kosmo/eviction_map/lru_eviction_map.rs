@@ -5,6 +5,8 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use serde::{Serialize, Deserialize};
+
 use crate::{
 	access::Access,
 	algorithm::Object,
@@ -15,11 +17,14 @@ use crate::{
 	},
 };
 
+#[derive(Serialize, Deserialize)]
 pub struct LruEvictionMap {
 	evicted_size: u64,
 }
 
 impl EvictionMap for LruEvictionMap {
+	type LocalObject<'a> = LocalObjectPolicy<'a>;
+
 	fn insert(&mut self, size: u64) {
 		self.evicted_size = size;
 	}
@@ -28,12 +33,9 @@ impl EvictionMap for LruEvictionMap {
 		self.evicted_size < size
 	}
 
-	fn reuse_distance(&self, object: &Object) -> u64 {
-		if self.evicted_size > 0 {
-			self.evicted_size
-		} else {
-			object.size as u64
-		}
+	fn reuse_distance(&self, _: &Object) -> u64 {
+		// the smallest cache size at which the object exists
+		self.evicted_size + 1
 	}
 
 	fn update(&mut self, access: &Access) {
@@ -92,4 +94,27 @@ mod tests {
 		assert!(eviction_map.exists_at(4));
 		assert!(eviction_map.exists_at(5));
 	}
+
+	#[test]
+	fn reuse_distance_is_correct() {
+		use crate::access::{Access, Command};
+		use crate::algorithm::Object;
+		use crate::kosmo::eviction_map::{EvictionMap, LruEvictionMap};
+
+		let access = Access {
+			timestamp: 0,
+			command: Command::Get,
+			key: 0,
+			size: 4,
+			ttl: None,
+		};
+
+		let object = Object::new(&access);
+
+		let mut eviction_map = LruEvictionMap::new(&access);
+		assert_eq!(eviction_map.reuse_distance(&object), 4);
+
+		eviction_map.insert(10);
+		assert_eq!(eviction_map.reuse_distance(&object), 11);
+	}
 }
@@ -0,0 +1,247 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::cmp;
+
+use serde::{Serialize, Deserialize};
+
+use crate::{
+	access::{Access, Timestamp},
+	algorithm::Object,
+	kosmo::{
+		eviction_map::EvictionMap,
+		global_object::GlobalObject,
+		local_object::{LocalObjectPolicy, LruKLocalObject},
+	},
+};
+
+/// The records of an LRU-K eviction map cover every cache size, since the
+/// access history of an object is retained after it is evicted.
+#[derive(Serialize, Deserialize)]
+pub struct LruKEvictionMap {
+	k: usize,
+	correlated_period: Timestamp,
+
+	map: Vec<EvictionRecord>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct EvictionRecord {
+	size: u64,
+
+	// the timestamps of the K most recent uncorrelated accesses, most
+	// recent first
+	history: Vec<Timestamp>,
+	last: Timestamp,
+
+	resident: bool,
+}
+
+impl EvictionMap for LruKEvictionMap {
+	type LocalObject<'a> = LocalObjectPolicy<'a>;
+
+	fn insert(&mut self, size: u64) {
+		let Some(index) = self.map.iter().position(|record| record.size <= size + 1) else {
+			return;
+		};
+
+		if self.map[index].size <= size {
+			let mut record = self.map[index].clone();
+			record.size = size + 1;
+
+			self.map.insert(index, record);
+		}
+
+		self.map
+			.iter_mut()
+			.filter(|record| record.size <= size)
+			.for_each(|record| record.resident = false);
+
+		self.coalesce();
+	}
+
+	fn exists_at(&self, size: u64) -> bool {
+		self.record_at(size).is_some_and(|record| record.resident)
+	}
+
+	fn reuse_distance(&self, object: &Object) -> u64 {
+		let smallest_resident = self.map
+			.iter()
+			.rev()
+			.find(|record| record.resident)
+			.map(|record| record.size);
+
+		match smallest_resident {
+			Some(size) => cmp::max(size, object.size as u64),
+			None => object.size as u64,
+		}
+	}
+
+	fn update(&mut self, access: &Access) {
+		for record in self.map.iter_mut() {
+			record.update(access.timestamp, self.k, self.correlated_period);
+		}
+
+		self.coalesce();
+	}
+
+	fn as_local_object<'a>(
+		&self,
+		global_object: &'a GlobalObject,
+		cache_size: u64,
+	) -> LocalObjectPolicy<'a> {
+		let local_object = LruKLocalObject::new(
+			global_object,
+			self.timestamps_at(cache_size),
+		);
+
+		LocalObjectPolicy::LruK(local_object)
+	}
+}
+
+impl LruKEvictionMap {
+	pub fn new(access: &Access, k: usize, correlated_period: Timestamp) -> Self {
+		assert!(k > 0);
+
+		let record = EvictionRecord {
+			size: 0,
+
+			history: vec![access.timestamp],
+			last: access.timestamp,
+
+			resident: true,
+		};
+
+		LruKEvictionMap {
+			k,
+			correlated_period,
+
+			map: vec![record],
+		}
+	}
+
+	/// Returns the K-th most recent uncorrelated access (or zero if there
+	/// were fewer than K accesses) and the most recent access, if the object
+	/// is resident at the supplied size.
+	pub fn timestamps_at(&self, size: u64) -> Option<(Timestamp, Timestamp)> {
+		self.record_at(size)
+			.filter(|record| record.resident)
+			.map(|record| {
+				let kth_timestamp = record.history
+					.get(self.k - 1)
+					.copied()
+					.unwrap_or(0);
+
+				(kth_timestamp, record.last)
+			})
+	}
+
+	fn record_at(&self, size: u64) -> Option<&EvictionRecord> {
+		self.map
+			.iter()
+			.find(|record| record.size <= size)
+	}
+
+	/// Merges neighbouring records which share a history.
+	fn coalesce(&mut self) {
+		self.map.dedup_by(|record, previous| {
+			if record.history != previous.history
+				|| record.last != previous.last
+				|| record.resident != previous.resident
+			{
+				return false;
+			}
+
+			previous.size = record.size;
+			true
+		});
+	}
+}
+
+impl EvictionRecord {
+	fn update(&mut self, timestamp: Timestamp, k: usize, correlated_period: Timestamp) {
+		if self.resident && timestamp - self.last <= correlated_period {
+			self.last = timestamp;
+			return;
+		}
+
+		if self.resident {
+			// the correlated accesses since the last uncorrelated access
+			// are collapsed into a single access
+			let correlated_duration = self.last - self.history[0];
+
+			for history_timestamp in self.history.iter_mut() {
+				*history_timestamp += correlated_duration;
+			}
+		}
+
+		self.history.insert(0, timestamp);
+		self.history.truncate(k);
+
+		self.last = timestamp;
+		self.resident = true;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn timestamps_are_correct() {
+		use crate::access::{Access, Command};
+		use crate::kosmo::eviction_map::{EvictionMap, LruKEvictionMap};
+
+		let mut access = Access {
+			timestamp: 1,
+			command: Command::Get,
+			key: 0,
+			size: 1,
+			ttl: None,
+		};
+
+		let mut eviction_map = LruKEvictionMap::new(&access, 2, 0);
+		assert_eq!(eviction_map.timestamps_at(100), Some((0, 1)));
+
+		access.timestamp += 1;
+		eviction_map.update(&access);
+		assert_eq!(eviction_map.timestamps_at(100), Some((1, 2)));
+
+		eviction_map.insert(50);
+		assert_eq!(eviction_map.timestamps_at(50), None);
+		assert!(!eviction_map.exists_at(50));
+		assert!(eviction_map.exists_at(51));
+
+		// the history of the object is retained after it is evicted
+		access.timestamp += 1;
+		eviction_map.update(&access);
+		assert_eq!(eviction_map.timestamps_at(50), Some((2, 3)));
+		assert_eq!(eviction_map.timestamps_at(51), Some((2, 3)));
+	}
+
+	#[test]
+	fn correlated_accesses_are_collapsed() {
+		use crate::access::{Access, Command};
+		use crate::kosmo::eviction_map::{EvictionMap, LruKEvictionMap};
+
+		let mut access = Access {
+			timestamp: 1,
+			command: Command::Get,
+			key: 0,
+			size: 1,
+			ttl: None,
+		};
+
+		let mut eviction_map = LruKEvictionMap::new(&access, 2, 5);
+
+		access.timestamp = 4;
+		eviction_map.update(&access);
+		assert_eq!(eviction_map.timestamps_at(1), Some((0, 4)));
+
+		access.timestamp = 20;
+		eviction_map.update(&access);
+		assert_eq!(eviction_map.timestamps_at(1), Some((4, 20)));
+	}
+}
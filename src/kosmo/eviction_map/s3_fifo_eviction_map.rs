@@ -0,0 +1,221 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::cmp;
+
+use serde::{Serialize, Deserialize};
+
+use crate::{
+	access::Access,
+	algorithm::Object,
+	kosmo::{
+		eviction_map::EvictionMap,
+		global_object::GlobalObject,
+		local_object::{
+			LocalObjectPolicy,
+			S3FifoLocalObject,
+			s3_fifo_local_object::QueueLocation,
+		},
+	},
+};
+
+const MAX_FREQUENCY: u8 = 3;
+
+/// Unlike the other eviction maps, the records of an S3-FIFO eviction
+/// map cover every cache size, since an object which is not resident
+/// in a cache may still be tracked by its ghost queue.
+#[derive(Serialize, Deserialize)]
+pub struct S3FifoEvictionMap {
+	map: Vec<EvictionRecord>,
+	last_evicted_size: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EvictionRecord {
+	size: u64,
+	queue_location: Option<QueueLocation>,
+	ghosted: bool,
+}
+
+impl EvictionMap for S3FifoEvictionMap {
+	type LocalObject<'a> = LocalObjectPolicy<'a>;
+
+	/// Evictions of an object are performed from the largest cache size
+	/// down, so an object which was moved to the ghost queue by a larger
+	/// eviction is kept in the ghost queue by the smaller evictions which
+	/// follow it.
+	fn insert(&mut self, size: u64) {
+		if self.last_evicted_size.is_none_or(|last_evicted_size| size >= last_evicted_size) {
+			self.map.iter_mut().for_each(|record| record.ghosted = false);
+		}
+
+		self.last_evicted_size = Some(size);
+
+		let Some(index) = self.map.iter().position(|record| record.size <= size + 1) else {
+			return;
+		};
+
+		if self.map[index].size <= size {
+			let queue_location = self.map[index].queue_location;
+			self.map.insert(index, EvictionRecord::new(size + 1, queue_location));
+		}
+
+		for record in self.map.iter_mut().filter(|record| record.size <= size) {
+			match record.queue_location {
+				Some(QueueLocation::Small(inserted_timestamp, _)) => {
+					record.queue_location = Some(QueueLocation::Ghost(inserted_timestamp));
+					record.ghosted = true;
+				},
+
+				Some(QueueLocation::Ghost(_)) if record.ghosted => {},
+				_ => record.queue_location = None,
+			}
+		}
+
+		self.coalesce();
+	}
+
+	fn exists_at(&self, size: u64) -> bool {
+		is_resident(self.queue_location_at(size))
+	}
+
+	fn reuse_distance(&self, object: &Object) -> u64 {
+		let smallest_resident = self.map
+			.iter()
+			.rev()
+			.find(|record| is_resident(record.queue_location))
+			.map(|record| record.size);
+
+		match smallest_resident {
+			Some(size) => cmp::max(size, object.size as u64),
+			None => object.size as u64,
+		}
+	}
+
+	fn update(&mut self, access: &Access) {
+		self.last_evicted_size = None;
+
+		for record in self.map.iter_mut() {
+			record.ghosted = false;
+
+			record.queue_location = match record.queue_location {
+				Some(QueueLocation::Small(inserted_timestamp, frequency)) => Some(QueueLocation::Small(
+					inserted_timestamp,
+					cmp::min(frequency + 1, MAX_FREQUENCY),
+				)),
+
+				Some(QueueLocation::Main) | Some(QueueLocation::Ghost(_)) => Some(QueueLocation::Main),
+				None => Some(QueueLocation::Small(access.timestamp, 0)),
+			};
+		}
+
+		self.coalesce();
+	}
+
+	fn as_local_object<'a>(
+		&self,
+		global_object: &'a GlobalObject,
+		cache_size: u64,
+	) -> LocalObjectPolicy<'a> {
+		let local_object = S3FifoLocalObject::new(
+			global_object,
+			self.queue_location_at(cache_size),
+		);
+
+		LocalObjectPolicy::S3Fifo(local_object)
+	}
+}
+
+impl S3FifoEvictionMap {
+	pub fn new(access: &Access) -> Self {
+		S3FifoEvictionMap {
+			map: vec![EvictionRecord::new(0, Some(QueueLocation::Small(access.timestamp, 0)))],
+			last_evicted_size: None,
+		}
+	}
+
+	pub fn queue_location_at(&self, size: u64) -> Option<QueueLocation> {
+		self.map
+			.iter()
+			.find(|record| record.size <= size)
+			.and_then(|record| record.queue_location)
+	}
+
+	/// Merges neighbouring records which share a queue location.
+	fn coalesce(&mut self) {
+		self.map.dedup_by(|record, previous| {
+			if record.queue_location != previous.queue_location || record.ghosted != previous.ghosted {
+				return false;
+			}
+
+			previous.size = record.size;
+			true
+		});
+	}
+}
+
+impl EvictionRecord {
+	fn new(size: u64, queue_location: Option<QueueLocation>) -> Self {
+		EvictionRecord {
+			size,
+			queue_location,
+			ghosted: false,
+		}
+	}
+}
+
+fn is_resident(queue_location: Option<QueueLocation>) -> bool {
+	matches!(
+		queue_location,
+		Some(QueueLocation::Small(..)) | Some(QueueLocation::Main),
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn queue_location_is_correct() {
+		use crate::{
+			access::{Access, Command},
+			kosmo::{
+				eviction_map::{EvictionMap, S3FifoEvictionMap},
+				local_object::s3_fifo_local_object::QueueLocation,
+			},
+		};
+
+		let mut access = Access {
+			timestamp: 1,
+			command: Command::Get,
+			key: 0,
+			size: 1,
+			ttl: None,
+		};
+
+		let mut eviction_map = S3FifoEvictionMap::new(&access);
+		assert_eq!(eviction_map.queue_location_at(100), Some(QueueLocation::Small(1, 0)));
+
+		eviction_map.insert(50);
+		assert_eq!(eviction_map.queue_location_at(50), Some(QueueLocation::Ghost(1)));
+		assert_eq!(eviction_map.queue_location_at(51), Some(QueueLocation::Small(1, 0)));
+
+		access.timestamp += 1;
+		eviction_map.update(&access);
+		assert_eq!(eviction_map.queue_location_at(50), Some(QueueLocation::Main));
+		assert_eq!(eviction_map.queue_location_at(51), Some(QueueLocation::Small(1, 1)));
+
+		eviction_map.insert(20);
+		assert_eq!(eviction_map.queue_location_at(20), None);
+		assert!(!eviction_map.exists_at(20));
+		assert!(eviction_map.exists_at(21));
+
+		access.timestamp += 1;
+		eviction_map.update(&access);
+		assert_eq!(eviction_map.queue_location_at(20), Some(QueueLocation::Small(3, 0)));
+		assert_eq!(eviction_map.queue_location_at(50), Some(QueueLocation::Main));
+		assert_eq!(eviction_map.queue_location_at(100), Some(QueueLocation::Small(1, 2)));
+	}
+}
@@ -0,0 +1,178 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::cmp;
+
+use serde::{Serialize, Deserialize};
+
+use crate::{
+	access::{Access, Timestamp},
+	algorithm::Object,
+	kosmo::{
+		eviction_map::EvictionMap,
+		global_object::GlobalObject,
+		local_object::{LocalObjectPolicy, SieveLocalObject},
+	},
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct SieveEvictionMap {
+	map: Vec<EvictionRecord>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EvictionRecord {
+	size: u64,
+	timestamp: Timestamp,
+	visited: bool,
+}
+
+impl EvictionMap for SieveEvictionMap {
+	type LocalObject<'a> = LocalObjectPolicy<'a>;
+
+	fn insert(&mut self, size: u64) {
+		if self.map.last().is_none_or(|record| record.size > size) {
+			return;
+		}
+
+		let mut updated_record = self.map.pop().unwrap();
+
+		while self.map.last().is_some_and(|record| record.size <= size) {
+			updated_record = self.map.pop().unwrap();
+		}
+
+		if self.map.last().is_none_or(|record| record.size != size + 1) {
+			updated_record.size = size + 1;
+			self.map.push(updated_record);
+		}
+	}
+
+	fn exists_at(&self, size: u64) -> bool {
+		self.record_at(size).is_some()
+	}
+
+	fn reuse_distance(&self, object: &Object) -> u64 {
+		match self.map.last() {
+			Some(record) => cmp::max(record.size, object.size as u64),
+			None => object.size as u64,
+		}
+	}
+
+	fn update(&mut self, access: &Access) {
+		self.map
+			.iter_mut()
+			.for_each(|record| record.visited = true);
+
+		let should_insert = match self.map.last() {
+			Some(record) => record.size != 0,
+			None => true,
+		};
+
+		if should_insert {
+			self.map.push(
+				EvictionRecord::new(0, access.timestamp, false)
+			);
+		}
+	}
+
+	fn as_local_object<'a>(
+		&self,
+		global_object: &'a GlobalObject,
+		cache_size: u64,
+	) -> LocalObjectPolicy<'a> {
+		let local_object = SieveLocalObject::new(
+			global_object,
+			self.timestamp_at(cache_size),
+			self.visited_at(cache_size).unwrap_or(false),
+		);
+
+		LocalObjectPolicy::Sieve(local_object)
+	}
+}
+
+impl SieveEvictionMap {
+	pub fn new(access: &Access) -> Self {
+		SieveEvictionMap {
+			map: vec![EvictionRecord::new(0, access.timestamp, false)],
+		}
+	}
+
+	pub fn timestamp_at(&self, size: u64) -> Option<Timestamp> {
+		self.record_at(size).map(|record| record.timestamp)
+	}
+
+	pub fn visited_at(&self, size: u64) -> Option<bool> {
+		self.record_at(size).map(|record| record.visited)
+	}
+
+	fn record_at(&self, size: u64) -> Option<&EvictionRecord> {
+		let mut current: Option<&EvictionRecord> = None;
+
+		for record in self.map.iter().rev() {
+			if record.size == size {
+				return Some(record);
+			}
+
+			if record.size > size {
+				return current;
+			}
+
+			current = Some(record);
+		}
+
+		current
+	}
+}
+
+impl EvictionRecord {
+	fn new(size: u64, timestamp: Timestamp, visited: bool) -> Self {
+		EvictionRecord {
+			size,
+			timestamp,
+			visited,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn record_is_correct() {
+		use crate::access::{Access, Command};
+		use crate::kosmo::eviction_map::{EvictionMap, SieveEvictionMap};
+
+		let mut access = Access {
+			timestamp: 1,
+			command: Command::Get,
+			key: 0,
+			size: 1,
+			ttl: None,
+		};
+
+		let mut eviction_map = SieveEvictionMap::new(&access);
+		assert_eq!(eviction_map.timestamp_at(1), Some(1));
+		assert_eq!(eviction_map.visited_at(1), Some(false));
+
+		access.timestamp += 1;
+		eviction_map.update(&access);
+		assert_eq!(eviction_map.timestamp_at(1), Some(1));
+		assert_eq!(eviction_map.visited_at(1), Some(true));
+
+		eviction_map.insert(5);
+		assert_eq!(eviction_map.timestamp_at(4), None);
+		assert_eq!(eviction_map.timestamp_at(5), None);
+		assert_eq!(eviction_map.timestamp_at(6), Some(1));
+		assert_eq!(eviction_map.visited_at(6), Some(true));
+
+		access.timestamp += 1;
+		eviction_map.update(&access);
+		assert_eq!(eviction_map.timestamp_at(4), Some(3));
+		assert_eq!(eviction_map.visited_at(4), Some(false));
+		assert_eq!(eviction_map.timestamp_at(6), Some(1));
+		assert_eq!(eviction_map.visited_at(6), Some(true));
+	}
+}
@@ -0,0 +1,181 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::cmp;
+
+use serde::{Serialize, Deserialize};
+
+use crate::{
+	access::{Access, Timestamp},
+	algorithm::Object,
+	kosmo::{
+		eviction_map::EvictionMap,
+		global_object::GlobalObject,
+		local_object::{LocalObjectPolicy, SlruLocalObject},
+	},
+};
+
+/// The records of an SLRU eviction map store the segment an object was
+/// last promoted into at each cache size. Demotions are not recorded, so
+/// an object which was demoted is promoted from its previous segment when
+/// it is accessed again.
+#[derive(Serialize, Deserialize)]
+pub struct SlruEvictionMap {
+	num_segments: usize,
+	map: Vec<EvictionRecord>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EvictionRecord {
+	size: u64,
+	segment: Option<usize>,
+	timestamp: Timestamp,
+}
+
+impl EvictionMap for SlruEvictionMap {
+	type LocalObject<'a> = LocalObjectPolicy<'a>;
+
+	fn insert(&mut self, size: u64) {
+		let Some(index) = self.map.iter().position(|record| record.size <= size + 1) else {
+			return;
+		};
+
+		if self.map[index].size <= size {
+			let (segment, timestamp) = (self.map[index].segment, self.map[index].timestamp);
+			self.map.insert(index, EvictionRecord::new(size + 1, segment, timestamp));
+		}
+
+		self.map
+			.iter_mut()
+			.filter(|record| record.size <= size)
+			.for_each(|record| record.segment = None);
+
+		self.coalesce();
+	}
+
+	fn exists_at(&self, size: u64) -> bool {
+		self.segment_at(size).is_some()
+	}
+
+	fn reuse_distance(&self, object: &Object) -> u64 {
+		let smallest_resident = self.map
+			.iter()
+			.rev()
+			.find(|record| record.segment.is_some())
+			.map(|record| record.size);
+
+		match smallest_resident {
+			Some(size) => cmp::max(size, object.size as u64),
+			None => object.size as u64,
+		}
+	}
+
+	fn update(&mut self, access: &Access) {
+		for record in self.map.iter_mut() {
+			record.segment = match record.segment {
+				Some(segment) => Some(cmp::min(segment + 1, self.num_segments - 1)),
+				None => Some(0),
+			};
+
+			record.timestamp = access.timestamp;
+		}
+
+		self.coalesce();
+	}
+
+	fn as_local_object<'a>(
+		&self,
+		global_object: &'a GlobalObject,
+		cache_size: u64,
+	) -> LocalObjectPolicy<'a> {
+		let record = self.record_at(cache_size);
+
+		let local_object = SlruLocalObject::new(
+			global_object,
+			record.and_then(|record| record.segment),
+			record.map_or(0, |record| record.timestamp),
+		);
+
+		LocalObjectPolicy::Slru(local_object)
+	}
+}
+
+impl SlruEvictionMap {
+	pub fn new(access: &Access, num_segments: usize) -> Self {
+		assert!(num_segments > 0);
+
+		SlruEvictionMap {
+			num_segments,
+			map: vec![EvictionRecord::new(0, Some(0), access.timestamp)],
+		}
+	}
+
+	pub fn segment_at(&self, size: u64) -> Option<usize> {
+		self.record_at(size).and_then(|record| record.segment)
+	}
+
+	fn record_at(&self, size: u64) -> Option<&EvictionRecord> {
+		self.map
+			.iter()
+			.find(|record| record.size <= size)
+	}
+
+	/// Merges neighbouring records which share a segment.
+	fn coalesce(&mut self) {
+		self.map.dedup_by(|record, previous| {
+			if record.segment != previous.segment || record.timestamp != previous.timestamp {
+				return false;
+			}
+
+			previous.size = record.size;
+			true
+		});
+	}
+}
+
+impl EvictionRecord {
+	fn new(size: u64, segment: Option<usize>, timestamp: Timestamp) -> Self {
+		EvictionRecord {
+			size,
+			segment,
+			timestamp,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn segment_is_correct() {
+		use crate::access::{Access, Command};
+		use crate::kosmo::eviction_map::{EvictionMap, SlruEvictionMap};
+
+		let mut access = Access {
+			timestamp: 1,
+			command: Command::Get,
+			key: 0,
+			size: 1,
+			ttl: None,
+		};
+
+		let mut eviction_map = SlruEvictionMap::new(&access, 2);
+		assert_eq!(eviction_map.segment_at(100), Some(0));
+
+		access.timestamp += 1;
+		eviction_map.update(&access);
+		assert_eq!(eviction_map.segment_at(100), Some(1));
+
+		eviction_map.insert(50);
+		assert_eq!(eviction_map.segment_at(50), None);
+		assert_eq!(eviction_map.segment_at(51), Some(1));
+
+		access.timestamp += 1;
+		eviction_map.update(&access);
+		assert_eq!(eviction_map.segment_at(50), Some(0));
+		assert_eq!(eviction_map.segment_at(51), Some(1));
+	}
+}
@@ -7,6 +7,8 @@
 
 use std::cmp;
 
+use serde::{Serialize, Deserialize};
+
 use crate::{
 	access::{Access, Timestamp},
 	algorithm::Object,
@@ -21,6 +23,7 @@ use crate::{
 	},
 };
 
+#[derive(Serialize, Deserialize)]
 pub struct TwoQEvictionMap {
 	kin: f64,
 	kout: f64,
@@ -31,17 +34,21 @@ pub struct TwoQEvictionMap {
 	lfu_map: Vec<LfuEvictionRecord>,
 }
 
+#[derive(Serialize, Deserialize)]
 struct FifoEvictionRecord {
 	size: u64,
 	timestamp: Timestamp,
 }
 
+#[derive(Serialize, Deserialize)]
 struct LfuEvictionRecord {
 	size: u64,
 	count: u64,
 }
 
 impl EvictionMap for TwoQEvictionMap {
+	type LocalObject<'a> = LocalObjectPolicy<'a>;
+
 	fn insert(&mut self, size: u64) {
 		self.insert_fifo(size);
 		self.insert_lfu(size);
@@ -136,10 +143,10 @@ impl TwoQEvictionMap {
 
 		let mut updated_timestamp: Timestamp = 0;
 
-		if self.fifo_map.last().is_some_and(|record| record.size <= size) {
-			if let Some(record) = self.fifo_map.pop() {
-				updated_timestamp = record.timestamp;
-			}
+		if self.fifo_map.last().is_some_and(|record| record.size <= size) &&
+			let Some(record) = self.fifo_map.pop()
+		{
+			updated_timestamp = record.timestamp;
 		}
 
 		while self.fifo_map.last().is_some_and(|record| record.size <= size) {
@@ -5,6 +5,8 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use serde::{Serialize, Deserialize};
+
 use crate::{
 	access::Access,
 	algorithm::Object,
@@ -14,6 +16,7 @@ use crate::{
 	},
 };
 
+#[derive(Serialize, Deserialize)]
 pub struct GlobalObject {
 	object: Object,
 	eviction_maps: Vec<EvictionMapPolicy>,
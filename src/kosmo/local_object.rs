@@ -10,6 +10,12 @@ pub mod fifo_local_object;
 pub mod two_q_local_object;
 pub mod lrfu_local_object;
 pub mod lru_local_object;
+pub mod sieve_local_object;
+pub mod s3_fifo_local_object;
+pub mod lirs_local_object;
+pub mod gds_local_object;
+pub mod lru_k_local_object;
+pub mod slru_local_object;
 
 use crate::access::{Key, Size};
 
@@ -26,6 +32,12 @@ pub enum LocalObjectPolicy<'a> {
 	TwoQ(TwoQLocalObject<'a>),
 	Lrfu(LrfuLocalObject<'a>),
 	Lru(LruLocalObject<'a>),
+	Sieve(SieveLocalObject<'a>),
+	S3Fifo(S3FifoLocalObject<'a>),
+	Lirs(LirsLocalObject<'a>),
+	Gds(GdsLocalObject<'a>),
+	LruK(LruKLocalObject<'a>),
+	Slru(SlruLocalObject<'a>),
 }
 
 impl<'a> LocalObject<'a> for LocalObjectPolicy<'a> {
@@ -36,6 +48,12 @@ impl<'a> LocalObject<'a> for LocalObjectPolicy<'a> {
 			LocalObjectPolicy::TwoQ(local_object) => local_object.key(),
 			LocalObjectPolicy::Lrfu(local_object) => local_object.key(),
 			LocalObjectPolicy::Lru(local_object) => local_object.key(),
+			LocalObjectPolicy::Sieve(local_object) => local_object.key(),
+			LocalObjectPolicy::S3Fifo(local_object) => local_object.key(),
+			LocalObjectPolicy::Lirs(local_object) => local_object.key(),
+			LocalObjectPolicy::Gds(local_object) => local_object.key(),
+			LocalObjectPolicy::LruK(local_object) => local_object.key(),
+			LocalObjectPolicy::Slru(local_object) => local_object.key(),
 		}
 	}
 
@@ -46,6 +64,12 @@ impl<'a> LocalObject<'a> for LocalObjectPolicy<'a> {
 			LocalObjectPolicy::TwoQ(local_object) => local_object.size(),
 			LocalObjectPolicy::Lrfu(local_object) => local_object.size(),
 			LocalObjectPolicy::Lru(local_object) => local_object.size(),
+			LocalObjectPolicy::Sieve(local_object) => local_object.size(),
+			LocalObjectPolicy::S3Fifo(local_object) => local_object.size(),
+			LocalObjectPolicy::Lirs(local_object) => local_object.size(),
+			LocalObjectPolicy::Gds(local_object) => local_object.size(),
+			LocalObjectPolicy::LruK(local_object) => local_object.size(),
+			LocalObjectPolicy::Slru(local_object) => local_object.size(),
 		}
 	}
 
@@ -56,6 +80,12 @@ impl<'a> LocalObject<'a> for LocalObjectPolicy<'a> {
 			LocalObjectPolicy::TwoQ(local_object) => local_object.exists(),
 			LocalObjectPolicy::Lrfu(local_object) => local_object.exists(),
 			LocalObjectPolicy::Lru(local_object) => local_object.exists(),
+			LocalObjectPolicy::Sieve(local_object) => local_object.exists(),
+			LocalObjectPolicy::S3Fifo(local_object) => local_object.exists(),
+			LocalObjectPolicy::Lirs(local_object) => local_object.exists(),
+			LocalObjectPolicy::Gds(local_object) => local_object.exists(),
+			LocalObjectPolicy::LruK(local_object) => local_object.exists(),
+			LocalObjectPolicy::Slru(local_object) => local_object.exists(),
 		}
 	}
 }
@@ -66,4 +96,10 @@ pub use crate::kosmo::local_object::{
 	two_q_local_object::TwoQLocalObject,
 	lrfu_local_object::LrfuLocalObject,
 	lru_local_object::LruLocalObject,
+	sieve_local_object::SieveLocalObject,
+	s3_fifo_local_object::S3FifoLocalObject,
+	lirs_local_object::LirsLocalObject,
+	gds_local_object::GdsLocalObject,
+	lru_k_local_object::LruKLocalObject,
+	slru_local_object::SlruLocalObject,
 };
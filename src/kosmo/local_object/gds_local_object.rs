@@ -0,0 +1,81 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::cmp::{Ord, Ordering};
+
+use crate::{
+	access::{Timestamp, Key, Size},
+	kosmo::global_object::GlobalObject,
+	kosmo::local_object::LocalObject,
+};
+
+pub struct GdsLocalObject<'a> {
+	global_object: &'a GlobalObject,
+	frequency: Option<u64>,
+
+	// the priority, H, which is set when the stack is reconstructed
+	priority: f64,
+}
+
+impl<'a> LocalObject<'a> for GdsLocalObject<'a> {
+	fn key(&self) -> Key {
+		self.global_object.object().key
+	}
+
+	fn size(&self) -> Size {
+		self.global_object.object().size
+	}
+
+	fn exists(&self) -> bool {
+		self.frequency.is_some()
+	}
+}
+
+impl<'a> GdsLocalObject<'a> {
+	pub fn new(global_object: &'a GlobalObject, frequency: Option<u64>) -> Self {
+		GdsLocalObject {
+			global_object,
+			frequency,
+
+			priority: 0.0,
+		}
+	}
+
+	pub fn frequency(&self) -> Option<u64> {
+		self.frequency
+	}
+
+	pub fn timestamp(&self) -> Timestamp {
+		self.global_object.object().timestamp
+	}
+
+	pub fn set_priority(&mut self, priority: f64) {
+		self.priority = priority;
+	}
+}
+
+impl<'a> Ord for GdsLocalObject<'a> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		other.priority
+			.total_cmp(&self.priority)
+			.then_with(|| other.timestamp().cmp(&self.timestamp()))
+	}
+}
+
+impl<'a> PartialOrd for GdsLocalObject<'a> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<'a> PartialEq for GdsLocalObject<'a> {
+	fn eq(&self, other: &Self) -> bool {
+		self.global_object.eq(other.global_object)
+	}
+}
+
+impl<'a> Eq for GdsLocalObject<'a> {}
@@ -0,0 +1,90 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::cmp::{Ord, Ordering};
+
+use serde::{Serialize, Deserialize};
+
+use crate::{
+	access::{Timestamp, Key, Size},
+	kosmo::global_object::GlobalObject,
+	kosmo::local_object::LocalObject,
+};
+
+pub struct LirsLocalObject<'a> {
+	global_object: &'a GlobalObject,
+	status: Option<LirsStatus>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LirsStatus {
+	Lir,
+	ResidentHir(Timestamp),
+	NonResidentHir,
+}
+
+impl<'a> LocalObject<'a> for LirsLocalObject<'a> {
+	fn key(&self) -> Key {
+		self.global_object.object().key
+	}
+
+	fn size(&self) -> Size {
+		self.global_object.object().size
+	}
+
+	fn exists(&self) -> bool {
+		matches!(
+			self.status,
+			Some(LirsStatus::Lir) | Some(LirsStatus::ResidentHir(_)),
+		)
+	}
+}
+
+impl<'a> LirsLocalObject<'a> {
+	pub fn new(
+		global_object: &'a GlobalObject,
+		status: Option<LirsStatus>,
+	) -> Self {
+		LirsLocalObject {
+			global_object,
+			status,
+		}
+	}
+
+	pub fn status(&self) -> Option<&LirsStatus> {
+		self.status.as_ref()
+	}
+
+	/// Resident HIR objects are ordered by the time they entered the
+	/// queue while all other objects are ordered by their last access.
+	pub fn timestamp(&self) -> Timestamp {
+		match self.status {
+			Some(LirsStatus::ResidentHir(queued_timestamp)) => queued_timestamp,
+			_ => self.global_object.object().timestamp,
+		}
+	}
+}
+
+impl<'a> Ord for LirsLocalObject<'a> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		other.timestamp().cmp(&self.timestamp())
+	}
+}
+
+impl<'a> PartialOrd for LirsLocalObject<'a> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<'a> PartialEq for LirsLocalObject<'a> {
+	fn eq(&self, other: &Self) -> bool {
+		self.global_object.eq(other.global_object)
+	}
+}
+
+impl<'a> Eq for LirsLocalObject<'a> {}
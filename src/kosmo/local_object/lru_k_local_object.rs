@@ -0,0 +1,72 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::cmp::{Ord, Ordering};
+
+use crate::{
+	access::{Timestamp, Key, Size},
+	kosmo::global_object::GlobalObject,
+	kosmo::local_object::LocalObject,
+};
+
+pub struct LruKLocalObject<'a> {
+	global_object: &'a GlobalObject,
+
+	// the K-th most recent uncorrelated access (or zero if there were fewer
+	// than K accesses) and the most recent access
+	timestamps: Option<(Timestamp, Timestamp)>,
+}
+
+impl<'a> LocalObject<'a> for LruKLocalObject<'a> {
+	fn key(&self) -> Key {
+		self.global_object.object().key
+	}
+
+	fn size(&self) -> Size {
+		self.global_object.object().size
+	}
+
+	fn exists(&self) -> bool {
+		self.timestamps.is_some()
+	}
+}
+
+impl<'a> LruKLocalObject<'a> {
+	pub fn new(
+		global_object: &'a GlobalObject,
+		timestamps: Option<(Timestamp, Timestamp)>,
+	) -> Self {
+		LruKLocalObject {
+			global_object,
+			timestamps,
+		}
+	}
+
+	pub fn last_timestamp(&self) -> Option<Timestamp> {
+		self.timestamps.map(|(_, last)| last)
+	}
+}
+
+impl<'a> Ord for LruKLocalObject<'a> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		other.timestamps.cmp(&self.timestamps)
+	}
+}
+
+impl<'a> PartialOrd for LruKLocalObject<'a> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<'a> PartialEq for LruKLocalObject<'a> {
+	fn eq(&self, other: &Self) -> bool {
+		self.global_object.eq(other.global_object)
+	}
+}
+
+impl<'a> Eq for LruKLocalObject<'a> {}
@@ -0,0 +1,91 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::cmp::{Ord, Ordering};
+
+use serde::{Serialize, Deserialize};
+
+use crate::{
+	access::{Timestamp, Key, Size},
+	kosmo::global_object::GlobalObject,
+	kosmo::local_object::LocalObject,
+};
+
+pub struct S3FifoLocalObject<'a> {
+	global_object: &'a GlobalObject,
+	queue_location: Option<QueueLocation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QueueLocation {
+	Small(Timestamp, u8),
+	Main,
+	Ghost(Timestamp),
+}
+
+impl<'a> LocalObject<'a> for S3FifoLocalObject<'a> {
+	fn key(&self) -> Key {
+		self.global_object.object().key
+	}
+
+	fn size(&self) -> Size {
+		self.global_object.object().size
+	}
+
+	fn exists(&self) -> bool {
+		matches!(
+			self.queue_location,
+			Some(QueueLocation::Small(..)) | Some(QueueLocation::Main),
+		)
+	}
+}
+
+impl<'a> S3FifoLocalObject<'a> {
+	pub fn new(
+		global_object: &'a GlobalObject,
+		queue_location: Option<QueueLocation>,
+	) -> Self {
+		S3FifoLocalObject {
+			global_object,
+			queue_location,
+		}
+	}
+
+	pub fn queue_location(&self) -> Option<&QueueLocation> {
+		self.queue_location.as_ref()
+	}
+
+	/// Objects in the main queue are ordered by their last access while
+	/// objects in the small and ghost queues are ordered by insertion.
+	fn timestamp(&self) -> Option<Timestamp> {
+		match self.queue_location? {
+			QueueLocation::Small(inserted_timestamp, _) => Some(inserted_timestamp),
+			QueueLocation::Main => Some(self.global_object.object().timestamp),
+			QueueLocation::Ghost(inserted_timestamp) => Some(inserted_timestamp),
+		}
+	}
+}
+
+impl<'a> Ord for S3FifoLocalObject<'a> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		other.timestamp().cmp(&self.timestamp())
+	}
+}
+
+impl<'a> PartialOrd for S3FifoLocalObject<'a> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<'a> PartialEq for S3FifoLocalObject<'a> {
+	fn eq(&self, other: &Self) -> bool {
+		self.global_object.eq(other.global_object)
+	}
+}
+
+impl<'a> Eq for S3FifoLocalObject<'a> {}
@@ -0,0 +1,78 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::cmp::{Ord, Ordering};
+
+use crate::{
+	access::{Timestamp, Key, Size},
+	kosmo::global_object::GlobalObject,
+	kosmo::local_object::LocalObject,
+};
+
+pub struct SieveLocalObject<'a> {
+	global_object: &'a GlobalObject,
+
+	inserted_timestamp: Option<Timestamp>,
+	visited: bool,
+}
+
+impl<'a> LocalObject<'a> for SieveLocalObject<'a> {
+	fn key(&self) -> Key {
+		self.global_object.object().key
+	}
+
+	fn size(&self) -> Size {
+		self.global_object.object().size
+	}
+
+	fn exists(&self) -> bool {
+		self.inserted_timestamp.is_some()
+	}
+}
+
+impl<'a> SieveLocalObject<'a> {
+	pub fn new(
+		global_object: &'a GlobalObject,
+		inserted_timestamp: Option<Timestamp>,
+		visited: bool,
+	) -> Self {
+		SieveLocalObject {
+			global_object,
+
+			inserted_timestamp,
+			visited,
+		}
+	}
+
+	pub fn visited(&self) -> bool {
+		self.visited
+	}
+
+	pub fn clear_visited(&mut self) {
+		self.visited = false;
+	}
+}
+
+impl<'a> Ord for SieveLocalObject<'a> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		other.inserted_timestamp.cmp(&self.inserted_timestamp)
+	}
+}
+
+impl<'a> PartialOrd for SieveLocalObject<'a> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<'a> PartialEq for SieveLocalObject<'a> {
+	fn eq(&self, other: &Self) -> bool {
+		self.global_object.eq(other.global_object)
+	}
+}
+
+impl<'a> Eq for SieveLocalObject<'a> {}
@@ -0,0 +1,85 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::cmp::{Ord, Ordering};
+
+use crate::{
+	access::{Timestamp, Key, Size},
+	kosmo::global_object::GlobalObject,
+	kosmo::local_object::LocalObject,
+};
+
+pub struct SlruLocalObject<'a> {
+	global_object: &'a GlobalObject,
+
+	segment: Option<usize>,
+	timestamp: Timestamp,
+}
+
+impl<'a> LocalObject<'a> for SlruLocalObject<'a> {
+	fn key(&self) -> Key {
+		self.global_object.object().key
+	}
+
+	fn size(&self) -> Size {
+		self.global_object.object().size
+	}
+
+	fn exists(&self) -> bool {
+		self.segment.is_some()
+	}
+}
+
+impl<'a> SlruLocalObject<'a> {
+	pub fn new(
+		global_object: &'a GlobalObject,
+		segment: Option<usize>,
+		timestamp: Timestamp,
+	) -> Self {
+		SlruLocalObject {
+			global_object,
+
+			segment,
+			timestamp,
+		}
+	}
+
+	pub fn segment(&self) -> Option<usize> {
+		self.segment
+	}
+
+	pub fn timestamp(&self) -> Timestamp {
+		self.timestamp
+	}
+
+	/// Moves the object to the head of the supplied segment, where the
+	/// supplied timestamp is newer than that of every object in it.
+	pub fn demote(&mut self, segment: usize, timestamp: Timestamp) {
+		self.segment = Some(segment);
+		self.timestamp = timestamp;
+	}
+}
+
+impl<'a> Ord for SlruLocalObject<'a> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		other.timestamp.cmp(&self.timestamp)
+	}
+}
+
+impl<'a> PartialOrd for SlruLocalObject<'a> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<'a> PartialEq for SlruLocalObject<'a> {
+	fn eq(&self, other: &Self) -> bool {
+		self.global_object.eq(other.global_object)
+	}
+}
+
+impl<'a> Eq for SlruLocalObject<'a> {}
@@ -0,0 +1,286 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::{
+	any::Any,
+	fmt::{self, Debug, Formatter},
+	sync::{Arc, LazyLock, RwLock},
+};
+
+use rustc_hash::FxHashMap;
+
+use crate::{
+	access::{Access, Key},
+	algorithm::Object,
+	kosmo::{
+		eviction_map::EvictionMap,
+		global_object::GlobalObject,
+		reconstructed_stack::ReconstructedStack,
+	},
+};
+
+static PLUGINS: LazyLock<RwLock<FxHashMap<String, Arc<dyn DynKosmoPlugin>>>> =
+	LazyLock::new(|| RwLock::new(FxHashMap::default()));
+
+/// A user-defined Kosmo policy. The plugin supplies an eviction map, which
+/// is stored with each object, and a reconstructed stack, which is built
+/// from the local objects created by the eviction maps.
+pub trait KosmoPlugin: Send + Sync + 'static {
+	type EvictionMap: EvictionMap + Send + Sync + 'static;
+
+	type ReconstructedStack<'a>: ReconstructedStack<
+		'a,
+		LocalObject = <Self::EvictionMap as EvictionMap>::LocalObject<'a>,
+	>;
+
+	/// Creates the eviction map of an object on its first access.
+	fn new_eviction_map(&self, access: &Access) -> Self::EvictionMap;
+
+	/// Creates an empty reconstructed stack of the supplied size.
+	fn new_reconstructed_stack<'a>(&self, size: u64) -> Self::ReconstructedStack<'a>;
+}
+
+/// A plugin policy, which can be used anywhere a `KosmoPolicy` is.
+#[derive(Clone)]
+pub struct KosmoPluginPolicy {
+	name: String,
+	plugin: Arc<dyn DynKosmoPlugin>,
+}
+
+/// Registers a plugin under the supplied policy name, replacing any plugin
+/// which was previously registered under it. Once registered, the policy
+/// can be parsed as a `KosmoPolicy` from its name, unless the name is that
+/// of a built-in policy without parameters (e.g., lru).
+pub fn register_plugin<P: KosmoPlugin>(name: &str, plugin: P) {
+	PLUGINS
+		.write()
+		.unwrap()
+		.insert(name.to_string(), Arc::new(plugin));
+}
+
+/// Returns the plugin policy registered under the supplied name, if any.
+pub fn find_plugin(name: &str) -> Option<KosmoPluginPolicy> {
+	let plugin = PLUGINS
+		.read()
+		.unwrap()
+		.get(name)
+		.cloned()?;
+
+	Some(KosmoPluginPolicy {
+		name: name.to_string(),
+		plugin,
+	})
+}
+
+impl KosmoPluginPolicy {
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	pub fn new_eviction_map(&self, access: &Access) -> Box<dyn DynEvictionMap> {
+		self.plugin.new_eviction_map(access)
+	}
+
+	pub fn new_reconstructed_stack<'a>(&self, size: u64) -> Box<dyn DynReconstructedStack<'a> + 'a> {
+		self.plugin.new_reconstructed_stack(size)
+	}
+}
+
+impl Debug for KosmoPluginPolicy {
+	fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+		formatter.debug_tuple("KosmoPluginPolicy").field(&self.name).finish()
+	}
+}
+
+impl PartialEq for KosmoPluginPolicy {
+	fn eq(&self, other: &Self) -> bool {
+		self.name == other.name
+	}
+}
+
+/// An eviction map whose type is erased, so that it can be stored with
+/// the built-in eviction maps.
+pub trait DynEvictionMap: Send + Sync {
+	fn insert(&mut self, _: u64);
+
+	fn exists_at(&self, _: u64) -> bool;
+	fn reuse_distance(&self, _: &Object) -> u64;
+
+	fn update(&mut self, _: &Access);
+
+	fn as_any(&self) -> &dyn Any;
+}
+
+/// A reconstructed stack whose type is erased. The local objects are
+/// created from the eviction maps by the stack itself, since only it
+/// knows their types.
+pub trait DynReconstructedStack<'a> {
+	fn insert(&mut self, _: &dyn DynEvictionMap, _: &'a GlobalObject, _: u64);
+	fn get_evictions(&mut self, _: Key) -> Vec<Key>;
+}
+
+trait DynKosmoPlugin: Send + Sync {
+	fn new_eviction_map(&self, _: &Access) -> Box<dyn DynEvictionMap>;
+	fn new_reconstructed_stack<'a>(&self, _: u64) -> Box<dyn DynReconstructedStack<'a> + 'a>;
+}
+
+struct PluginReconstructedStack<'a, P: KosmoPlugin> {
+	stack: P::ReconstructedStack<'a>,
+}
+
+impl<P: KosmoPlugin> DynKosmoPlugin for P {
+	fn new_eviction_map(&self, access: &Access) -> Box<dyn DynEvictionMap> {
+		Box::new(KosmoPlugin::new_eviction_map(self, access))
+	}
+
+	fn new_reconstructed_stack<'a>(&self, size: u64) -> Box<dyn DynReconstructedStack<'a> + 'a> {
+		Box::new(PluginReconstructedStack::<P> {
+			stack: KosmoPlugin::new_reconstructed_stack(self, size),
+		})
+	}
+}
+
+impl<T: EvictionMap + Send + Sync + 'static> DynEvictionMap for T {
+	fn insert(&mut self, size: u64) {
+		EvictionMap::insert(self, size);
+	}
+
+	fn exists_at(&self, size: u64) -> bool {
+		EvictionMap::exists_at(self, size)
+	}
+
+	fn reuse_distance(&self, object: &Object) -> u64 {
+		EvictionMap::reuse_distance(self, object)
+	}
+
+	fn update(&mut self, access: &Access) {
+		EvictionMap::update(self, access);
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+}
+
+impl<'a, P: KosmoPlugin> DynReconstructedStack<'a> for PluginReconstructedStack<'a, P> {
+	fn insert(
+		&mut self,
+		eviction_map: &dyn DynEvictionMap,
+		global_object: &'a GlobalObject,
+		cache_size: u64,
+	) {
+		let Some(eviction_map) = eviction_map.as_any().downcast_ref::<P::EvictionMap>() else {
+			panic!("Invalid eviction map type for plugin reconstructed stack.");
+		};
+
+		self.stack.insert(eviction_map.as_local_object(global_object, cache_size));
+	}
+
+	fn get_evictions(&mut self, exclude_key: Key) -> Vec<Key> {
+		self.stack.get_evictions(exclude_key)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use crate::{
+		access::{Access, Command},
+		algorithm::{Algorithm, Object},
+		curve::Interpolation,
+		kosmo::{
+			Kosmo,
+			KosmoPolicy,
+			KosmoPlugin,
+			eviction_map::{EvictionMap, LruEvictionMap},
+			global_object::GlobalObject,
+			local_object::LruLocalObject,
+			reconstructed_stack::LruReconstructedStack,
+			register_plugin,
+		},
+	};
+
+	struct PluginLruEvictionMap(LruEvictionMap);
+	struct PluginLru;
+
+	impl EvictionMap for PluginLruEvictionMap {
+		type LocalObject<'a> = LruLocalObject<'a>;
+
+		fn insert(&mut self, size: u64) {
+			self.0.insert(size);
+		}
+
+		fn exists_at(&self, size: u64) -> bool {
+			self.0.exists_at(size)
+		}
+
+		fn reuse_distance(&self, object: &Object) -> u64 {
+			self.0.reuse_distance(object)
+		}
+
+		fn update(&mut self, access: &Access) {
+			self.0.update(access);
+		}
+
+		fn as_local_object<'a>(
+			&self,
+			global_object: &'a GlobalObject,
+			cache_size: u64,
+		) -> LruLocalObject<'a> {
+			LruLocalObject::new(global_object, self.0.exists_at(cache_size))
+		}
+	}
+
+	impl KosmoPlugin for PluginLru {
+		type EvictionMap = PluginLruEvictionMap;
+		type ReconstructedStack<'a> = LruReconstructedStack<'a>;
+
+		fn new_eviction_map(&self, access: &Access) -> PluginLruEvictionMap {
+			PluginLruEvictionMap(LruEvictionMap::new(access))
+		}
+
+		fn new_reconstructed_stack<'a>(&self, size: u64) -> LruReconstructedStack<'a> {
+			LruReconstructedStack::new(size)
+		}
+	}
+
+	#[test]
+	fn plugin_matches_builtin() {
+		register_plugin("plugin-lru", PluginLru);
+
+		let plugin_policy = KosmoPolicy::from_str("plugin-lru").unwrap();
+		let mut kosmo = Kosmo::new(&[KosmoPolicy::Lru, plugin_policy.clone()], None);
+
+		for index in 0..5_000u64 {
+			let access = Access {
+				timestamp: index + 1,
+				command: Command::Get,
+				key: index * 7 % 13 + index % 500,
+				size: (index % 7 + 1) as u32 * 100,
+				ttl: None,
+			};
+
+			kosmo.handle(&access);
+		}
+
+		let lru_curve = kosmo.policy_curve(&KosmoPolicy::Lru).unwrap();
+		let plugin_curve = kosmo.policy_curve(&plugin_policy).unwrap();
+
+		assert!(!lru_curve.is_empty());
+		assert_eq!(lru_curve.mae(&plugin_curve, Interpolation::Step), 0.0);
+	}
+
+	#[test]
+	fn plugin_with_builtin_prefix_is_found() {
+		register_plugin("lru2", PluginLru);
+
+		let policy = KosmoPolicy::from_str("lru2").unwrap();
+
+		assert!(matches!(policy, KosmoPolicy::Plugin(plugin) if plugin.name() == "lru2"));
+	}
+}
@@ -16,6 +16,13 @@ use serde::{
 	de::{self, Deserializer, Visitor},
 };
 
+use crate::{
+	access::Timestamp,
+	policy_config,
+	cache::GdsCost,
+	kosmo::plugin::{self, KosmoPluginPolicy},
+};
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum KosmoPolicy {
 	Lfu,
@@ -23,6 +30,14 @@ pub enum KosmoPolicy {
 	TwoQ(f64, f64),
 	Lrfu(f64, f64),
 	Lru,
+	Sieve,
+	S3Fifo(f64, f64),
+	Lirs(f64),
+	Gds(GdsCost),
+	Gdsf(GdsCost),
+	LruK(usize, Timestamp),
+	Slru(Vec<f64>),
+	Plugin(KosmoPluginPolicy),
 }
 
 impl FromStr for KosmoPolicy {
@@ -33,13 +48,17 @@ impl FromStr for KosmoPolicy {
 			"lfu" => Ok(KosmoPolicy::Lfu),
 			"fifo" => Ok(KosmoPolicy::Fifo),
 			"lru" => Ok(KosmoPolicy::Lru),
+			"sieve" => Ok(KosmoPolicy::Sieve),
 			"2q" => Ok(KosmoPolicy::TwoQ(0.25, 0.5)),
 			"lrfu" => Ok(KosmoPolicy::Lrfu(2.0, 0.5)),
 
-			_ => Err(Error::new(
-				ErrorKind::InvalidData,
-				"Invalid Kosmo policy.",
-			)),
+			// plugins are found before the configurable policies, so that a
+			// plugin whose name starts with the name of a built-in policy
+			// (e.g., lru2) can be selected
+			value => match plugin::find_plugin(value) {
+				Some(plugin) => Ok(KosmoPolicy::Plugin(plugin)),
+				None => parse_config(value),
+			},
 		}
 	}
 }
@@ -70,3 +89,27 @@ impl<'a> Visitor<'a> for KosmoPolicyVisitor {
 			.map_err(|err| E::custom(err.to_string()))
 	}
 }
+
+/// Parses the config of a built-in policy which takes parameters.
+fn parse_config(value: &str) -> Result<KosmoPolicy, Error> {
+	match value {
+		value if value.starts_with("s3fifo") => policy_config::parse_s3_fifo_config(value)
+			.map(|(small_ratio, ghost_ratio)| KosmoPolicy::S3Fifo(small_ratio, ghost_ratio)),
+		value if value.starts_with("lirs") => policy_config::parse_lirs_config(value)
+			.map(KosmoPolicy::Lirs),
+		value if value.starts_with("gds") => policy_config::parse_gds_config(value)
+			.map(|(frequency, cost)| match frequency {
+				true => KosmoPolicy::Gdsf(cost),
+				false => KosmoPolicy::Gds(cost),
+			}),
+		value if value.starts_with("lruk") => policy_config::parse_lru_k_config(value)
+			.map(|(k, correlated_period)| KosmoPolicy::LruK(k, correlated_period)),
+		value if value.starts_with("slru") => policy_config::parse_slru_config(value)
+			.map(KosmoPolicy::Slru),
+
+		_ => Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid Kosmo policy.",
+		)),
+	}
+}
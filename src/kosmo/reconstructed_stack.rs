@@ -10,12 +10,21 @@ mod fifo_reconstructed_stack;
 mod two_q_reconstructed_stack;
 mod lrfu_reconstructed_stack;
 mod lru_reconstructed_stack;
+mod sieve_reconstructed_stack;
+mod s3_fifo_reconstructed_stack;
+mod lirs_reconstructed_stack;
+mod gds_reconstructed_stack;
+mod lru_k_reconstructed_stack;
+mod slru_reconstructed_stack;
 
 use crate::{
 	access::Key,
 	kosmo::{
 		KosmoPolicy,
+		global_object::GlobalObject,
+		eviction_map::{EvictionMap, EvictionMapPolicy},
 		local_object::{LocalObject, LocalObjectPolicy},
+		plugin::DynReconstructedStack,
 	},
 };
 
@@ -38,13 +47,19 @@ pub trait ReconstructedStack<'a> {
 	}
 }
 
-
 pub enum ReconstructedStackPolicy<'a> {
 	Lfu(LfuReconstructedStack<'a>),
 	Fifo(FifoReconstructedStack<'a>),
 	TwoQ(TwoQReconstructedStack<'a>),
 	Lrfu(LrfuReconstructedStack<'a>),
 	Lru(LruReconstructedStack<'a>),
+	Sieve(SieveReconstructedStack<'a>),
+	S3Fifo(S3FifoReconstructedStack<'a>),
+	Lirs(LirsReconstructedStack<'a>),
+	Gds(GdsReconstructedStack<'a>),
+	LruK(LruKReconstructedStack<'a>),
+	Slru(SlruReconstructedStack<'a>),
+	Plugin(Box<dyn DynReconstructedStack<'a> + 'a>),
 }
 
 impl<'a> ReconstructedStackPolicy<'a> {
@@ -69,6 +84,56 @@ impl<'a> ReconstructedStackPolicy<'a> {
 			KosmoPolicy::Lru => ReconstructedStackPolicy::Lru(
 				LruReconstructedStack::new(size)
 			),
+
+			KosmoPolicy::Sieve => ReconstructedStackPolicy::Sieve(
+				SieveReconstructedStack::new(size)
+			),
+
+			KosmoPolicy::S3Fifo(small_ratio, ghost_ratio) => ReconstructedStackPolicy::S3Fifo(
+				S3FifoReconstructedStack::new(size, *small_ratio, *ghost_ratio)
+			),
+
+			KosmoPolicy::Lirs(hir_ratio) => ReconstructedStackPolicy::Lirs(
+				LirsReconstructedStack::new(size, *hir_ratio)
+			),
+
+			KosmoPolicy::Gds(cost) => ReconstructedStackPolicy::Gds(
+				GdsReconstructedStack::new(size, *cost, false)
+			),
+
+			KosmoPolicy::Gdsf(cost) => ReconstructedStackPolicy::Gds(
+				GdsReconstructedStack::new(size, *cost, true)
+			),
+
+			KosmoPolicy::LruK(_, correlated_period) => ReconstructedStackPolicy::LruK(
+				LruKReconstructedStack::new(size, *correlated_period)
+			),
+
+			KosmoPolicy::Slru(ratios) => ReconstructedStackPolicy::Slru(
+				SlruReconstructedStack::new(size, ratios)
+			),
+
+			KosmoPolicy::Plugin(plugin) => ReconstructedStackPolicy::Plugin(
+				plugin.new_reconstructed_stack(size)
+			),
+		}
+	}
+
+	/// Inserts the local object of the supplied eviction map at the size of
+	/// the stack. Plugin stacks create their own local objects, since their
+	/// types are only known to the plugin.
+	pub fn insert_from(
+		&mut self,
+		eviction_map: &EvictionMapPolicy,
+		global_object: &'a GlobalObject,
+		size: u64,
+	) {
+		match (self, eviction_map) {
+			(ReconstructedStackPolicy::Plugin(stack), EvictionMapPolicy::Plugin(eviction_map))
+				=> stack.insert(eviction_map.as_ref(), global_object, size),
+
+			(stack, eviction_map)
+				=> stack.insert(eviction_map.as_local_object(global_object, size)),
 		}
 	}
 
@@ -89,6 +154,24 @@ impl<'a> ReconstructedStackPolicy<'a> {
 			(ReconstructedStackPolicy::Lru(stack), LocalObjectPolicy::Lru(local_object))
 				=> stack.insert(local_object),
 
+			(ReconstructedStackPolicy::Sieve(stack), LocalObjectPolicy::Sieve(local_object))
+				=> stack.insert(local_object),
+
+			(ReconstructedStackPolicy::S3Fifo(stack), LocalObjectPolicy::S3Fifo(local_object))
+				=> stack.insert(local_object),
+
+			(ReconstructedStackPolicy::Lirs(stack), LocalObjectPolicy::Lirs(local_object))
+				=> stack.insert(local_object),
+
+			(ReconstructedStackPolicy::Gds(stack), LocalObjectPolicy::Gds(local_object))
+				=> stack.insert(local_object),
+
+			(ReconstructedStackPolicy::LruK(stack), LocalObjectPolicy::LruK(local_object))
+				=> stack.insert(local_object),
+
+			(ReconstructedStackPolicy::Slru(stack), LocalObjectPolicy::Slru(local_object))
+				=> stack.insert(local_object),
+
 			_ => panic!("Invalid local object type for reconstructed stack."),
 		}
 	}
@@ -100,6 +183,13 @@ impl<'a> ReconstructedStackPolicy<'a> {
 			ReconstructedStackPolicy::TwoQ(stack) => stack.get_evictions(exclude_key),
 			ReconstructedStackPolicy::Lrfu(stack) => stack.get_evictions(exclude_key),
 			ReconstructedStackPolicy::Lru(stack) => stack.get_evictions(exclude_key),
+			ReconstructedStackPolicy::Sieve(stack) => stack.get_evictions(exclude_key),
+			ReconstructedStackPolicy::S3Fifo(stack) => stack.get_evictions(exclude_key),
+			ReconstructedStackPolicy::Lirs(stack) => stack.get_evictions(exclude_key),
+			ReconstructedStackPolicy::Gds(stack) => stack.get_evictions(exclude_key),
+			ReconstructedStackPolicy::LruK(stack) => stack.get_evictions(exclude_key),
+			ReconstructedStackPolicy::Slru(stack) => stack.get_evictions(exclude_key),
+			ReconstructedStackPolicy::Plugin(stack) => stack.get_evictions(exclude_key),
 		}
 	}
 }
@@ -110,4 +200,10 @@ pub use crate::kosmo::reconstructed_stack::{
 	two_q_reconstructed_stack::TwoQReconstructedStack,
 	lrfu_reconstructed_stack::LrfuReconstructedStack,
 	lru_reconstructed_stack::LruReconstructedStack,
+	sieve_reconstructed_stack::SieveReconstructedStack,
+	s3_fifo_reconstructed_stack::S3FifoReconstructedStack,
+	lirs_reconstructed_stack::LirsReconstructedStack,
+	gds_reconstructed_stack::GdsReconstructedStack,
+	lru_k_reconstructed_stack::LruKReconstructedStack,
+	slru_reconstructed_stack::SlruReconstructedStack,
 };
@@ -44,10 +44,8 @@ impl<'a> ReconstructedStack<'a> for FifoReconstructedStack<'a> {
 			local_object.size()
 		));
 
-		if let Some((key, size)) = evicted {
-			if key != exclude_key {
-				self.used_size -= size as u64;
-			}
+		if let Some((key, size)) = evicted && key != exclude_key {
+			self.used_size -= size as u64;
 		}
 
 		evicted.map(|(key, _)| key)
@@ -0,0 +1,123 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::{
+	cmp,
+	collections::BinaryHeap,
+};
+
+use crate::{
+	access::Key,
+	cache::GdsCost,
+	kosmo::{
+		reconstructed_stack::ReconstructedStack,
+		local_object::{LocalObject, GdsLocalObject},
+	},
+};
+
+/// The priority of an object is H = L + F * C / S, where L is the value
+/// of the cache's inflation when the object was last accessed. Since the
+/// inflation is not stored between accesses, it is approximated as growing
+/// linearly with time, by the mean value (F * C / S) of the objects in the
+/// cache over the age of the oldest object in the cache.
+pub struct GdsReconstructedStack<'a> {
+	max_size: u64,
+	used_size: u64,
+
+	cost: GdsCost,
+	frequency: bool,
+
+	local_objects: Vec<GdsLocalObject<'a>>,
+	stack: BinaryHeap<GdsLocalObject<'a>>,
+}
+
+impl<'a> ReconstructedStack<'a> for GdsReconstructedStack<'a> {
+	type LocalObject = GdsLocalObject<'a>;
+
+	fn insert(&mut self, local_object: GdsLocalObject<'a>) {
+		let object_size = local_object.size();
+
+		if local_object.exists() {
+			self.local_objects.push(local_object);
+			self.used_size += object_size as u64;
+		}
+	}
+
+	fn get_eviction(&mut self, exclude_key: Key) -> Option<Key> {
+		if self.used_size <= self.max_size {
+			return None;
+		}
+
+		if !self.local_objects.is_empty() {
+			self.prioritize();
+		}
+
+		let evicted = self.stack.pop().map(|local_object| (
+			local_object.key(),
+			local_object.size()
+		));
+
+		if let Some((key, size)) = evicted && key != exclude_key {
+			self.used_size -= size as u64;
+		}
+
+		evicted.map(|(key, _)| key)
+	}
+}
+
+impl<'a> GdsReconstructedStack<'a> {
+	pub fn new(max_size: u64, cost: GdsCost, frequency: bool) -> Self {
+		GdsReconstructedStack {
+			max_size,
+			used_size: 0,
+
+			cost,
+			frequency,
+
+			local_objects: Vec::new(),
+			stack: BinaryHeap::new(),
+		}
+	}
+
+	fn value(&self, local_object: &GdsLocalObject) -> f64 {
+		let frequency = match self.frequency {
+			true => local_object.frequency().unwrap_or(1),
+			false => 1,
+		};
+
+		self.cost.value(local_object.size(), frequency)
+	}
+
+	fn prioritize(&mut self) {
+		let oldest_timestamp = self.local_objects
+			.iter()
+			.map(|local_object| local_object.timestamp())
+			.min()
+			.unwrap_or(0);
+
+		let newest_timestamp = self.local_objects
+			.iter()
+			.map(|local_object| local_object.timestamp())
+			.max()
+			.unwrap_or(0);
+
+		let mean_value = self.local_objects
+			.iter()
+			.map(|local_object| self.value(local_object))
+			.sum::<f64>() / self.local_objects.len() as f64;
+
+		let inflation_rate = mean_value / cmp::max(newest_timestamp - oldest_timestamp, 1) as f64;
+
+		for mut local_object in std::mem::take(&mut self.local_objects) {
+			let inflation = inflation_rate * (local_object.timestamp() - oldest_timestamp) as f64;
+			let priority = inflation + self.value(&local_object);
+
+			local_object.set_priority(priority);
+			self.stack.push(local_object);
+		}
+	}
+}
@@ -0,0 +1,123 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::collections::{BinaryHeap, VecDeque};
+
+use crate::{
+	access::Key,
+	kosmo::{
+		reconstructed_stack::ReconstructedStack,
+		local_object::{
+			LocalObject,
+			LirsLocalObject,
+			lirs_local_object::LirsStatus,
+		},
+	},
+};
+
+/// LIR objects which no longer fit in their portion of the cache are
+/// demoted to the end of the resident HIR queue. Non-resident HIR objects
+/// which were last accessed before the oldest LIR object are pruned from
+/// the LIRS stack and reported as evictions.
+pub struct LirsReconstructedStack<'a> {
+	max_size: u64,
+
+	lir_used_size: u64,
+	hir_used_size: u64,
+
+	hir_ratio: f64,
+
+	lir: BinaryHeap<LirsLocalObject<'a>>,
+	hir: BinaryHeap<LirsLocalObject<'a>>,
+	demoted: VecDeque<LirsLocalObject<'a>>,
+	non_resident: BinaryHeap<LirsLocalObject<'a>>,
+}
+
+impl<'a> ReconstructedStack<'a> for LirsReconstructedStack<'a> {
+	type LocalObject = LirsLocalObject<'a>;
+
+	fn insert(&mut self, local_object: LirsLocalObject<'a>) {
+		let object_size = local_object.size() as u64;
+
+		match local_object.status() {
+			Some(LirsStatus::Lir) => {
+				self.lir.push(local_object);
+				self.lir_used_size += object_size;
+			},
+
+			Some(LirsStatus::ResidentHir(_)) => {
+				self.hir.push(local_object);
+				self.hir_used_size += object_size;
+			},
+
+			Some(LirsStatus::NonResidentHir) => self.non_resident.push(local_object),
+			None => {},
+		}
+	}
+
+	fn get_eviction(&mut self, exclude_key: Key) -> Option<Key> {
+		let lir_size = (self.max_size as f64 * (1.0 - self.hir_ratio)) as u64;
+
+		while self.lir_used_size > lir_size {
+			let Some(local_object) = self.lir.pop() else {
+				break;
+			};
+
+			let object_size = local_object.size() as u64;
+
+			self.lir_used_size -= object_size;
+			self.hir_used_size += object_size;
+
+			self.demoted.push_back(local_object);
+		}
+
+		if self.lir_used_size + self.hir_used_size > self.max_size {
+			let local_object = self.hir
+				.pop()
+				.or_else(|| self.demoted.pop_front())?;
+
+			let key = local_object.key();
+
+			if key != exclude_key {
+				self.hir_used_size -= local_object.size() as u64;
+			}
+
+			return Some(key);
+		}
+
+		let bottom_timestamp = self.lir
+			.peek()
+			.map(|local_object| local_object.timestamp());
+
+		let should_prune = self.non_resident
+			.peek()
+			.is_some_and(|local_object| bottom_timestamp.is_none_or(|timestamp| local_object.timestamp() < timestamp));
+
+		match should_prune {
+			true => self.non_resident.pop().map(|local_object| local_object.key()),
+			false => None,
+		}
+	}
+}
+
+impl<'a> LirsReconstructedStack<'a> {
+	pub fn new(max_size: u64, hir_ratio: f64) -> Self {
+		LirsReconstructedStack {
+			max_size,
+
+			lir_used_size: 0,
+			hir_used_size: 0,
+
+			hir_ratio,
+
+			lir: BinaryHeap::new(),
+			hir: BinaryHeap::new(),
+			demoted: VecDeque::new(),
+			non_resident: BinaryHeap::new(),
+		}
+	}
+}
@@ -0,0 +1,100 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::{
+	cmp,
+	collections::{BinaryHeap, VecDeque},
+};
+
+use crate::{
+	access::{Key, Timestamp},
+	kosmo::{
+		reconstructed_stack::ReconstructedStack,
+		local_object::{LocalObject, LruKLocalObject},
+	},
+};
+
+pub struct LruKReconstructedStack<'a> {
+	max_size: u64,
+	used_size: u64,
+
+	correlated_period: Timestamp,
+
+	// the most recent access to any object (i.e., the current time)
+	timestamp: Timestamp,
+
+	stack: BinaryHeap<LruKLocalObject<'a>>,
+
+	// the objects which were accessed within the correlated reference
+	// period, which are only evicted once every other object is
+	deferred: VecDeque<LruKLocalObject<'a>>,
+}
+
+impl<'a> ReconstructedStack<'a> for LruKReconstructedStack<'a> {
+	type LocalObject = LruKLocalObject<'a>;
+
+	fn insert(&mut self, local_object: LruKLocalObject<'a>) {
+		let object_size = local_object.size();
+
+		if let Some(last_timestamp) = local_object.last_timestamp() {
+			self.timestamp = cmp::max(self.timestamp, last_timestamp);
+
+			self.stack.push(local_object);
+			self.used_size += object_size as u64;
+		}
+	}
+
+	fn get_eviction(&mut self, exclude_key: Key) -> Option<Key> {
+		if self.used_size <= self.max_size {
+			return None;
+		}
+
+		while let Some(local_object) = self.stack.pop() {
+			if self.is_correlated(&local_object) {
+				self.deferred.push_back(local_object);
+				continue;
+			}
+
+			return Some(self.evict(local_object, exclude_key));
+		}
+
+		self.deferred
+			.pop_front()
+			.map(|local_object| self.evict(local_object, exclude_key))
+	}
+}
+
+impl<'a> LruKReconstructedStack<'a> {
+	pub fn new(max_size: u64, correlated_period: Timestamp) -> Self {
+		LruKReconstructedStack {
+			max_size,
+			used_size: 0,
+
+			correlated_period,
+			timestamp: 0,
+
+			stack: BinaryHeap::new(),
+			deferred: VecDeque::new(),
+		}
+	}
+
+	fn is_correlated(&self, local_object: &LruKLocalObject) -> bool {
+		local_object
+			.last_timestamp()
+			.is_some_and(|last_timestamp| self.timestamp - last_timestamp <= self.correlated_period)
+	}
+
+	fn evict(&mut self, local_object: LruKLocalObject, exclude_key: Key) -> Key {
+		let key = local_object.key();
+
+		if key != exclude_key {
+			self.used_size -= local_object.size() as u64;
+		}
+
+		key
+	}
+}
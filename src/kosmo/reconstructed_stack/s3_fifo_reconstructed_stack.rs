@@ -0,0 +1,140 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::collections::BinaryHeap;
+
+use crate::{
+	access::Key,
+	kosmo::{
+		reconstructed_stack::ReconstructedStack,
+		local_object::{
+			LocalObject,
+			S3FifoLocalObject,
+			s3_fifo_local_object::QueueLocation,
+		},
+	},
+};
+
+/// The main queue is reconstructed in recency order as an approximation
+/// of its reinsertion of objects which have been accessed. Objects which
+/// outgrow the ghost queue are reported as evictions so that they are
+/// dropped from the ghost queue at this cache size.
+pub struct S3FifoReconstructedStack<'a> {
+	max_size: u64,
+
+	small_used_size: u64,
+	main_used_size: u64,
+	ghost_used_size: u64,
+
+	small_ratio: f64,
+	ghost_ratio: f64,
+
+	small: BinaryHeap<S3FifoLocalObject<'a>>,
+	main: BinaryHeap<S3FifoLocalObject<'a>>,
+	ghost: BinaryHeap<S3FifoLocalObject<'a>>,
+}
+
+impl<'a> ReconstructedStack<'a> for S3FifoReconstructedStack<'a> {
+	type LocalObject = S3FifoLocalObject<'a>;
+
+	fn insert(&mut self, local_object: S3FifoLocalObject<'a>) {
+		let object_size = local_object.size() as u64;
+
+		match local_object.queue_location() {
+			Some(QueueLocation::Small(..)) => {
+				self.small.push(local_object);
+				self.small_used_size += object_size;
+			},
+
+			Some(QueueLocation::Main) => {
+				self.main.push(local_object);
+				self.main_used_size += object_size;
+			},
+
+			Some(QueueLocation::Ghost(_)) => {
+				self.ghost.push(local_object);
+				self.ghost_used_size += object_size;
+			},
+
+			None => {},
+		}
+	}
+
+	fn get_eviction(&mut self, exclude_key: Key) -> Option<Key> {
+		let small_size = (self.max_size as f64 * self.small_ratio) as u64;
+
+		while self.small_used_size + self.main_used_size > self.max_size {
+			if self.small_used_size >= small_size || self.main.is_empty() {
+				let Some(local_object) = self.small.pop() else {
+					break;
+				};
+
+				let key = local_object.key();
+				let object_size = local_object.size() as u64;
+
+				if key == exclude_key {
+					return Some(key);
+				}
+
+				self.small_used_size -= object_size;
+
+				let promote = matches!(
+					local_object.queue_location(),
+					Some(QueueLocation::Small(_, frequency)) if *frequency > 1,
+				);
+
+				if promote {
+					self.main.push(local_object);
+					self.main_used_size += object_size;
+
+					continue;
+				}
+
+				return Some(key);
+			}
+
+			let local_object = self.main.pop()?;
+			let key = local_object.key();
+
+			if key != exclude_key {
+				self.main_used_size -= local_object.size() as u64;
+			}
+
+			return Some(key);
+		}
+
+		let ghost_size = (self.max_size as f64 * self.ghost_ratio) as u64;
+
+		if self.ghost_used_size <= ghost_size {
+			return None;
+		}
+
+		let local_object = self.ghost.pop()?;
+		self.ghost_used_size -= local_object.size() as u64;
+
+		Some(local_object.key())
+	}
+}
+
+impl<'a> S3FifoReconstructedStack<'a> {
+	pub fn new(max_size: u64, small_ratio: f64, ghost_ratio: f64) -> Self {
+		S3FifoReconstructedStack {
+			max_size,
+
+			small_used_size: 0,
+			main_used_size: 0,
+			ghost_used_size: 0,
+
+			small_ratio,
+			ghost_ratio,
+
+			small: BinaryHeap::new(),
+			main: BinaryHeap::new(),
+			ghost: BinaryHeap::new(),
+		}
+	}
+}
@@ -0,0 +1,88 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::collections::{BinaryHeap, VecDeque};
+
+use crate::{
+	access::Key,
+	kosmo::{
+		reconstructed_stack::ReconstructedStack,
+		local_object::{LocalObject, SieveLocalObject},
+	},
+};
+
+/// Since neither the position of the hand nor the cleared visited bits
+/// are stored between accesses, each reconstruction sweeps the queue
+/// starting from its tail, passing over any object which has been
+/// visited since it was inserted.
+pub struct SieveReconstructedStack<'a> {
+	max_size: u64,
+	used_size: u64,
+
+	stack: BinaryHeap<SieveLocalObject<'a>>,
+	swept: VecDeque<SieveLocalObject<'a>>,
+}
+
+impl<'a> ReconstructedStack<'a> for SieveReconstructedStack<'a> {
+	type LocalObject = SieveLocalObject<'a>;
+
+	fn insert(&mut self, local_object: SieveLocalObject<'a>) {
+		let object_size = local_object.size();
+
+		if local_object.exists() {
+			self.stack.push(local_object);
+			self.used_size += object_size as u64;
+		}
+	}
+
+	fn get_eviction(&mut self, exclude_key: Key) -> Option<Key> {
+		if self.used_size <= self.max_size {
+			return None;
+		}
+
+		while let Some(mut local_object) = self.next_local_object() {
+			let key = local_object.key();
+
+			if key == exclude_key {
+				return Some(key);
+			}
+
+			if local_object.visited() {
+				local_object.clear_visited();
+				self.swept.push_back(local_object);
+
+				continue;
+			}
+
+			self.used_size -= local_object.size() as u64;
+
+			return Some(key);
+		}
+
+		None
+	}
+}
+
+impl<'a> SieveReconstructedStack<'a> {
+	pub fn new(max_size: u64) -> Self {
+		SieveReconstructedStack {
+			max_size,
+			used_size: 0,
+
+			stack: BinaryHeap::new(),
+			swept: VecDeque::new(),
+		}
+	}
+
+	/// Returns the object under the hand, wrapping around to the objects
+	/// which have already been swept once the head of the queue is reached.
+	fn next_local_object(&mut self) -> Option<SieveLocalObject<'a>> {
+		self.stack
+			.pop()
+			.or_else(|| self.swept.pop_front())
+	}
+}
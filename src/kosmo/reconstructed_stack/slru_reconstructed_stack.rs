@@ -0,0 +1,108 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::{
+	cmp,
+	collections::BinaryHeap,
+};
+
+use crate::{
+	access::{Key, Timestamp},
+	kosmo::{
+		reconstructed_stack::ReconstructedStack,
+		local_object::{LocalObject, SlruLocalObject},
+	},
+};
+
+/// The segments are reconstructed from the segment each object was last
+/// promoted into. Objects which overflow a segment are demoted to the head
+/// of the previous segment before any object is evicted.
+pub struct SlruReconstructedStack<'a> {
+	max_size: u64,
+	ratios: Vec<f64>,
+
+	// the timestamp given to the next demoted object, which is newer than
+	// that of any object in the stack
+	demotion_timestamp: Timestamp,
+
+	used_sizes: Vec<u64>,
+	segments: Vec<BinaryHeap<SlruLocalObject<'a>>>,
+}
+
+impl<'a> ReconstructedStack<'a> for SlruReconstructedStack<'a> {
+	type LocalObject = SlruLocalObject<'a>;
+
+	fn insert(&mut self, local_object: SlruLocalObject<'a>) {
+		let object_size = local_object.size();
+
+		if let Some(segment) = local_object.segment() {
+			self.demotion_timestamp = cmp::max(self.demotion_timestamp, local_object.timestamp() + 1);
+
+			self.segments[segment].push(local_object);
+			self.used_sizes[segment] += object_size as u64;
+		}
+	}
+
+	fn get_eviction(&mut self, exclude_key: Key) -> Option<Key> {
+		self.demote_objects();
+
+		if self.used_sizes.iter().sum::<u64>() <= self.max_size {
+			return None;
+		}
+
+		let segment = self.segments.iter().position(|segment| !segment.is_empty())?;
+
+		let evicted = self.segments[segment].pop().map(|local_object| (
+			local_object.key(),
+			local_object.size()
+		));
+
+		if let Some((key, size)) = evicted && key != exclude_key {
+			self.used_sizes[segment] -= size as u64;
+		}
+
+		evicted.map(|(key, _)| key)
+	}
+}
+
+impl<'a> SlruReconstructedStack<'a> {
+	pub fn new(max_size: u64, ratios: &[f64]) -> Self {
+		SlruReconstructedStack {
+			max_size,
+			ratios: ratios.to_vec(),
+
+			demotion_timestamp: 0,
+
+			used_sizes: vec![0; ratios.len()],
+			segments: ratios.iter().map(|_| BinaryHeap::new()).collect(),
+		}
+	}
+
+	fn segment_max_size(&self, segment: usize) -> u64 {
+		(self.ratios[segment] * self.max_size as f64) as u64
+	}
+
+	fn demote_objects(&mut self) {
+		for segment in (1..self.segments.len()).rev() {
+			while self.used_sizes[segment] > self.segment_max_size(segment) {
+				let Some(mut local_object) = self.segments[segment].pop() else {
+					break;
+				};
+
+				let object_size = local_object.size() as u64;
+
+				local_object.demote(segment - 1, self.demotion_timestamp);
+				self.demotion_timestamp += 1;
+
+				self.segments[segment - 1].push(local_object);
+
+				self.used_sizes[segment] -= object_size;
+				self.used_sizes[segment - 1] += object_size;
+			}
+		}
+	}
+}
@@ -92,10 +92,8 @@ impl<'a> TwoQReconstructedStack<'a> {
 			local_object.size()
 		));
 
-		if let Some((key, size)) = evicted {
-			if key != exclude_key {
-				self.a1_used_size -= size as u64;
-			}
+		if let Some((key, size)) = evicted && key != exclude_key {
+			self.a1_used_size -= size as u64;
 		}
 
 		evicted.map(|(key, _)| key)
@@ -107,10 +105,8 @@ impl<'a> TwoQReconstructedStack<'a> {
 			local_object.size()
 		));
 
-		if let Some((key, size)) = evicted {
-			if key != exclude_key {
-				self.am_used_size -= size as u64;
-			}
+		if let Some((key, size)) = evicted && key != exclude_key {
+			self.am_used_size -= size as u64;
 		}
 
 		evicted.map(|(key, _)| key)
@@ -0,0 +1,250 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::{
+	fs::File,
+	io::{BufWriter, Error, ErrorKind, Write},
+	str::FromStr,
+};
+
+use crate::curve::{Curve, Interpolation};
+
+/// Turns an MRC into the expected mean access latency and backend load of
+/// a cache hierarchy. The first tier is the cache whose size is varied,
+/// and it is followed by any number of lower tiers of fixed sizes (e.g.,
+/// a flash tier below a DRAM cache) before the backend. The tiers are
+/// exclusive, so a request which misses in the first tier of a total size
+/// is served by the first tier below it whose cumulative size holds it.
+#[derive(Clone, Debug)]
+pub struct LatencyModel {
+	hit_latency: f64,
+	miss_latency: f64,
+	request_rate: f64,
+	lower_tiers: Vec<LatencyTier>,
+}
+
+/// A lower tier of a cache hierarchy.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LatencyTier {
+	pub size: u64,
+	pub latency: f64,
+}
+
+/// The command line arguments which configure a latency model, shared by
+/// the tools which save latencies alongside their curves.
+#[derive(clap::Args, Clone, Debug)]
+pub struct LatencyArgs {
+	/// The file to which the mean access latency and backend load at each
+	/// size of the curves are saved (as CSV).
+	#[arg(long, requires_all = ["hit_latency", "miss_latency", "request_rate"])]
+	pub latency_output: Option<String>,
+
+	/// The latency of a hit in the cache (in any unit).
+	#[arg(long)]
+	pub hit_latency: Option<f64>,
+
+	/// The latency of a miss which is served by the backend (in the unit
+	/// of the hit latency).
+	#[arg(long)]
+	pub miss_latency: Option<f64>,
+
+	/// The number of requests per second to the cache.
+	#[arg(long)]
+	pub request_rate: Option<f64>,
+
+	/// The tiers below the cache (<size>:<latency>), in order, which serve
+	/// its misses before the backend.
+	#[arg(long, value_delimiter = ',')]
+	pub tier: Vec<LatencyTier>,
+}
+
+/// The expected performance of a cache of some size.
+#[derive(Clone, Debug)]
+pub struct Performance {
+	pub size: u64,
+
+	/// The miss ratio of the first tier.
+	pub miss_ratio: f64,
+
+	/// The fraction of requests which are served by the backend.
+	pub backend_miss_ratio: f64,
+
+	pub mean_latency: f64,
+
+	/// The number of requests per second which are served by the backend.
+	pub backend_rate: f64,
+}
+
+impl LatencyModel {
+	/// Returns a model of a single cache which receives the supplied number
+	/// of requests per second, where hits and misses take the supplied
+	/// latencies (in any unit).
+	pub fn new(hit_latency: f64, miss_latency: f64, request_rate: f64) -> Self {
+		assert!(hit_latency >= 0.0 && miss_latency >= 0.0 && request_rate >= 0.0);
+
+		LatencyModel {
+			hit_latency,
+			miss_latency,
+			request_rate,
+			lower_tiers: Vec::new(),
+		}
+	}
+
+	/// Adds a tier below the existing tiers.
+	pub fn with_tier(mut self, tier: LatencyTier) -> Self {
+		assert!(tier.latency >= 0.0, "Tier latencies must be non-negative.");
+
+		self.lower_tiers.push(tier);
+		self
+	}
+
+	/// Returns the expected performance with a first tier of the supplied
+	/// size.
+	pub fn performance(&self, curve: &Curve, size: u64, interpolation: Interpolation) -> Performance {
+		let miss_ratio = curve.get_interpolated_miss_ratio(size, interpolation);
+
+		let mut mean_latency = (1.0 - miss_ratio) * self.hit_latency;
+		let mut cumulative_size = size;
+		let mut prev_miss_ratio = miss_ratio;
+
+		for tier in &self.lower_tiers {
+			cumulative_size += tier.size;
+
+			// misses are non-increasing through the hierarchy
+			let tier_miss_ratio = curve
+				.get_interpolated_miss_ratio(cumulative_size, interpolation)
+				.min(prev_miss_ratio);
+
+			mean_latency += (prev_miss_ratio - tier_miss_ratio) * tier.latency;
+			prev_miss_ratio = tier_miss_ratio;
+		}
+
+		mean_latency += prev_miss_ratio * self.miss_latency;
+
+		Performance {
+			size,
+			miss_ratio,
+			backend_miss_ratio: prev_miss_ratio,
+			mean_latency,
+			backend_rate: prev_miss_ratio * self.request_rate,
+		}
+	}
+
+	/// Returns the expected performance at each size of the MRC's points.
+	pub fn performances(&self, curve: &Curve, interpolation: Interpolation) -> Vec<Performance> {
+		curve
+			.into_iter()
+			.map(|point| self.performance(curve, point.get_size(), interpolation))
+			.collect()
+	}
+}
+
+impl LatencyArgs {
+	/// Returns the latency model configured by the arguments, if a latency
+	/// output was supplied.
+	pub fn model(&self) -> Option<LatencyModel> {
+		self.latency_output.as_ref()?;
+
+		let model = LatencyModel::new(
+			self.hit_latency?,
+			self.miss_latency?,
+			self.request_rate?,
+		);
+
+		Some(self.tier
+			.iter()
+			.fold(model, |model, tier| model.with_tier(*tier)))
+	}
+}
+
+/// Saves the performances of each named curve to a CSV file with a header,
+/// and one row per curve and size.
+pub fn to_file(path: &str, curves: &[(&str, Vec<Performance>)]) -> Result<(), Error> {
+	let mut writer = BufWriter::new(File::create(path)?);
+
+	writeln!(writer, "curve,size,miss_ratio,backend_miss_ratio,mean_latency,backend_rate")?;
+
+	for (name, performances) in curves {
+		for performance in performances {
+			writeln!(
+				writer,
+				"{name},{},{},{},{},{}",
+				performance.size,
+				performance.miss_ratio,
+				performance.backend_miss_ratio,
+				performance.mean_latency,
+				performance.backend_rate,
+			)?;
+		}
+	}
+
+	writer.flush()
+}
+
+impl FromStr for LatencyTier {
+	type Err = Error;
+
+	/// Parses a tier from `<size>:<latency>`.
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		let invalid = || Error::new(
+			ErrorKind::InvalidData,
+			"Invalid latency tier (expected <size>:<latency>).",
+		);
+
+		let (size, latency) = value.split_once(':').ok_or_else(invalid)?;
+
+		Ok(LatencyTier {
+			size: size.parse::<u64>().map_err(|_| invalid())?,
+			latency: latency.parse::<f64>().map_err(|_| invalid())?,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use crate::{
+		curve::{Curve, Interpolation},
+		latency::{LatencyModel, LatencyTier},
+		test_utils::assert_close,
+	};
+
+	fn curve() -> Curve {
+		Curve::from_points(&[(100, 0.5), (200, 0.3), (300, 0.2)])
+	}
+
+	#[test]
+	fn single_tier_latency() {
+		let model = LatencyModel::new(1.0, 10.0, 1_000.0);
+		let performance = model.performance(&curve(), 100, Interpolation::Step);
+
+		assert_close(performance.mean_latency, 0.5 * 1.0 + 0.5 * 10.0);
+		assert_close(performance.backend_rate, 500.0);
+
+		let performances = model.performances(&curve(), Interpolation::Step);
+		assert_eq!(performances.len(), 3);
+		assert_close(performances[2].backend_rate, 200.0);
+	}
+
+	#[test]
+	fn lower_tiers_serve_misses() {
+		let model = LatencyModel::new(1.0, 10.0, 1_000.0)
+			.with_tier(LatencyTier::from_str("200:4").unwrap());
+
+		let performance = model.performance(&curve(), 100, Interpolation::Step);
+
+		// half of the requests hit the first tier, and the second tier
+		// serves the requests which hit at a total size of 300
+		assert_close(performance.miss_ratio, 0.5);
+		assert_close(performance.backend_miss_ratio, 0.2);
+		assert_close(performance.mean_latency, 0.5 * 1.0 + 0.3 * 4.0 + 0.2 * 10.0);
+		assert_close(performance.backend_rate, 200.0);
+
+		assert!(LatencyTier::from_str("200").is_err());
+	}
+}
@@ -0,0 +1,35 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! The library interface of Kosmo, which allows external crates to run the
+//! MRC generation algorithms and to register their own Kosmo and cache
+//! policies (see `kosmo::register_plugin` and `cache::register_plugin`).
+
+#![feature(btree_cursors)]
+
+pub mod access;
+pub mod algorithm;
+pub mod histogram;
+pub mod curve;
+pub mod curve_plot;
+pub mod curve_comparison;
+pub mod curve_set;
+pub mod grid;
+pub mod shards;
+pub mod cache;
+pub mod kosmo;
+pub mod minisimulations;
+pub mod opt;
+pub mod checkpoint;
+pub mod allocation;
+pub mod cost;
+pub mod latency;
+
+mod policy_config;
+
+#[cfg(test)]
+mod test_utils;
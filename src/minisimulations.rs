@@ -5,22 +5,28 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use std::io::{Error, ErrorKind, Read, Write};
+
 use rayon::prelude::*;
 
 use crate::{
 	access::{Access, Key},
-	algorithm::Algorithm,
+	algorithm::{self, Algorithm},
 	shards::Shards,
 	curve::Curve,
 	cache::{Cache, CachePolicy},
+	checkpoint,
+	grid::SizeGrid,
 };
 
-const NUM_CACHES: u32 = 100;
-
 /// The MiniSim MRC generation algorithm.
 pub struct Minisimulations {
-	max_cache_size: u64,
-	caches: Vec<Box<dyn Cache>>,
+	policies: Vec<CachePolicy>,
+	cache_sizes: Vec<u64>,
+
+	// the simulated caches of each policy (one per cache size), which
+	// share the SHARDS sampling decision of each access
+	caches: Vec<Vec<Box<dyn Cache>>>,
 
 	shards: Option<Box<dyn Shards>>,
 	shards_global_t: u64,
@@ -40,6 +46,7 @@ impl Algorithm for Minisimulations {
 
 		self.caches
 			.par_iter_mut()
+			.flatten()
 			.for_each(|cache| {
 				cache.handle_self_populating(access);
 			});
@@ -48,41 +55,32 @@ impl Algorithm for Minisimulations {
 	fn remove(&mut self, key: Key) {
 		self.caches
 			.par_iter_mut()
+			.flatten()
 			.for_each(|cache| cache.del(key));
 	}
 
 	fn clean(&mut self) {
 		self.caches
 			.par_iter_mut()
+			.flatten()
 			.for_each(|cache| cache.clear_counters());
 	}
 
 	fn resize(&mut self, size: u64) {
 		self.caches
 			.par_iter_mut()
+			.flatten()
 			.for_each(|cache| cache.reduce(size));
 	}
 
 	fn curve(&mut self) -> Curve {
-		let mut curve = Curve::new();
-
-		for cache in &self.caches {
-			let mut cache_size = cache.size();
-			let mut miss_ratio = cache.miss_ratio();
-
-			if let Some(shards) = &self.shards {
-				cache_size = shards.unscale(cache_size);
-
-				miss_ratio = (
-					(miss_ratio * shards.get_sampled_count() as f64) /
-						shards.get_expected_count() as f64
-				).clamp(0.0, 1.0);
-			}
-
-			curve.add(cache_size, miss_ratio);
-		}
+		self.curve_at(0)
+	}
 
-		curve
+	fn curves(&mut self) -> Vec<Curve> {
+		(0..self.policies.len())
+			.map(|policy_index| self.curve_at(policy_index))
+			.collect()
 	}
 
 	fn verify_shards(&mut self, access: &Access) -> bool {
@@ -98,20 +96,75 @@ impl Algorithm for Minisimulations {
 
 		true
 	}
+
+	fn save(&self, writer: &mut dyn Write) -> Result<(), Error> {
+		checkpoint::save(writer, &format!("{:?}", self.policies))?;
+		checkpoint::save(writer, &self.cache_sizes)?;
+		checkpoint::save(writer, &self.shards_global_t)?;
+
+		for cache in self.caches.iter().flatten() {
+			cache.save(writer)?;
+		}
+
+		checkpoint::save_shards(writer, self.shards.as_deref())
+	}
+
+	fn restore(&mut self, reader: &mut dyn Read) -> Result<(), Error> {
+		if checkpoint::restore::<String>(reader)? != format!("{:?}", self.policies) {
+			return Err(Error::new(
+				ErrorKind::InvalidData,
+				"The checkpoint was saved with different MiniSim policies.",
+			));
+		}
+
+		if checkpoint::restore::<Vec<u64>>(reader)? != self.cache_sizes {
+			return Err(Error::new(
+				ErrorKind::InvalidData,
+				"The checkpoint was saved with a different MiniSim configuration.",
+			));
+		}
+
+		self.shards_global_t = checkpoint::restore(reader)?;
+
+		for cache in self.caches.iter_mut().flatten() {
+			cache.restore(reader)?;
+		}
+
+		checkpoint::restore_shards(reader, &mut self.shards)
+	}
 }
 
 impl Minisimulations {
 	pub fn new(
-		policy: &CachePolicy,
+		policies: &[CachePolicy],
 		max_cache_size: u64,
 		shards: Option<Box<dyn Shards>>,
 	) -> Self {
-		let caches = get_caches(
-			policy,
+		Minisimulations::with_grid(
+			policies,
 			max_cache_size,
-			NUM_CACHES,
-			shards.as_deref(),
-		);
+			&SizeGrid::default(),
+			shards,
+		)
+	}
+
+	/// Creates a MiniSim instance which simulates a cache of each policy at
+	/// each size of the supplied grid.
+	pub fn with_grid(
+		policies: &[CachePolicy],
+		max_cache_size: u64,
+		grid: &SizeGrid,
+		shards: Option<Box<dyn Shards>>,
+	) -> Self {
+		assert!(!policies.is_empty(), "MiniSim must be configured with at least one policy.");
+		assert!(!algorithm::has_duplicates(policies), "MiniSim cannot have duplicate policies.");
+
+		let cache_sizes = grid.sizes(max_cache_size);
+
+		let caches = policies
+			.iter()
+			.map(|policy| get_caches(policy, &cache_sizes, shards.as_deref()))
+			.collect::<Vec<Vec<Box<dyn Cache>>>>();
 
 		let shards_global_t = shards
 			.as_ref()
@@ -119,7 +172,8 @@ impl Minisimulations {
 			.unwrap_or(0);
 
 		Minisimulations {
-			max_cache_size,
+			policies: policies.to_vec(),
+			cache_sizes,
 			caches,
 
 			shards,
@@ -127,9 +181,39 @@ impl Minisimulations {
 		}
 	}
 
+	/// Returns the MRC of the supplied policy, if it exists.
+	pub fn policy_curve(&self, policy: &CachePolicy) -> Option<Curve> {
+		let policy_index = self.policies
+			.iter()
+			.position(|minisim_policy| minisim_policy == policy)?;
+
+		Some(self.curve_at(policy_index))
+	}
+
+	fn curve_at(&self, policy_index: usize) -> Curve {
+		let mut curve = Curve::new();
+
+		for cache in &self.caches[policy_index] {
+			let mut cache_size = cache.size();
+			let mut miss_ratio = cache.miss_ratio();
+
+			if let Some(shards) = &self.shards {
+				cache_size = shards.unscale(cache_size);
+
+				miss_ratio = (
+					(miss_ratio * shards.get_sampled_count() as f64) /
+						shards.get_expected_count() as f64
+				).clamp(0.0, 1.0);
+			}
+
+			curve.add(cache_size, miss_ratio);
+		}
+
+		curve
+	}
+
 	fn rescale(&mut self, shards_new_global_t: u64) {
 		let ratio = shards_new_global_t as f64 / self.shards_global_t as f64;
-		let num_caches = self.caches.len() as u64;
 
 		let shards_rate = self.shards
 			.as_ref()
@@ -138,11 +222,9 @@ impl Minisimulations {
 
 		self.caches
 			.par_iter_mut()
-			.enumerate()
+			.flat_map(|caches| caches.par_iter_mut().enumerate())
 			.for_each(|(index, cache)| {
-				let cache_size = (index as u64 + 1) as f64 *
-					(self.max_cache_size / num_caches) as f64 *
-					shards_rate;
+				let cache_size = self.cache_sizes[index] as f64 * shards_rate;
 
 				cache.resize(cache_size as u64);
 				cache.rescale(ratio);
@@ -152,13 +234,13 @@ impl Minisimulations {
 
 fn get_caches(
 	policy: &CachePolicy,
-	max_cache_size: u64,
-	num_caches: u32,
+	cache_sizes: &[u64],
 	shards: Option<&dyn Shards>
 ) -> Vec<Box<dyn Cache>> {
-	(1..=num_caches)
-		.map(|i| {
-			let mut cache_size = (i as u64) * (max_cache_size / num_caches as u64);
+	cache_sizes
+		.iter()
+		.map(|cache_size| {
+			let mut cache_size = *cache_size;
 
 			if let Some(shards) = shards {
 				cache_size = shards.scale(cache_size);
@@ -168,3 +250,74 @@ fn get_caches(
 		})
 		.collect::<Vec<Box<dyn Cache>>>()
 }
+
+#[cfg(test)]
+mod tests {
+	use std::{slice, str::FromStr};
+
+	use crate::{
+		access::{Access, Command},
+		algorithm::Algorithm,
+		cache::CachePolicy,
+		curve::Interpolation,
+		minisimulations::Minisimulations,
+		shards::{Shards, ShardsFixedSize},
+	};
+
+	fn trace() -> Vec<Access> {
+		let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+
+		(0..3_000)
+			.map(|index| {
+				state ^= state << 13;
+				state ^= state >> 7;
+				state ^= state << 17;
+
+				let key = state % 400;
+
+				Access {
+					timestamp: index + 1,
+					command: Command::Get,
+					key,
+					size: (key % 4 + 1) as u32 * 1024,
+					ttl: None,
+				}
+			})
+			.collect()
+	}
+
+	#[test]
+	fn policies_match_separate_runs() {
+		let policies = ["lru", "fifo", "sieve", "s3fifo", "arc"]
+			.map(|policy| CachePolicy::from_str(policy).unwrap());
+
+		let shards = || -> Option<Box<dyn Shards>> {
+			Some(Box::new(ShardsFixedSize::new(1 << 23, 64)))
+		};
+
+		let mut minisim = Minisimulations::new(&policies, 1 << 20, shards());
+
+		for access in &trace() {
+			minisim.handle(access);
+		}
+
+		let curves = minisim.curves();
+		assert_eq!(curves.len(), policies.len());
+
+		for (policy, curve) in policies.iter().zip(&curves) {
+			let mut separate = Minisimulations::new(slice::from_ref(policy), 1 << 20, shards());
+
+			for access in &trace() {
+				separate.handle(access);
+			}
+
+			let expected = separate.curve();
+
+			assert!(!expected.is_empty());
+			assert_eq!(expected.mae(curve, Interpolation::Step), 0.0);
+			assert_eq!(minisim.policy_curve(policy).unwrap().mae(curve, Interpolation::Step), 0.0);
+		}
+
+		assert!(minisim.policy_curve(&CachePolicy::Lfu).is_none());
+	}
+}
@@ -5,20 +5,13 @@
  * LICENSE file in the root directory of this source tree.
  */
 
-#![feature(btree_cursors)]
-
-mod access;
-mod algorithm;
-mod histogram;
-mod curve;
-mod curve_plot;
-mod shards;
-mod cache;
-mod kosmo;
-mod minisimulations;
+use std::{
+	io::{self, Error},
+	str::FromStr,
+	time::Instant,
+};
 
-use std::time::Instant;
-use clap::{Parser, ValueEnum};
+use clap::{CommandFactory, Parser, ValueEnum, error::ErrorKind};
 
 use kwik::{
 	fmt,
@@ -28,21 +21,26 @@ use kwik::{
 		binary::{BinaryReader, SizedChunk},
 	},
 	progress::{Progress, Tag},
-	plot::Figure,
 };
 
-use crate::{
+use kosmo_fast24::{
 	access::Access,
 	shards::{Shards, ShardsFixedRate, ShardsFixedSize},
 	algorithm::Algorithm,
 	cache::CachePolicy,
-	kosmo::{Kosmo, KosmoPolicy},
+	kosmo::{self, Kosmo, KosmoPolicy},
 	minisimulations::Minisimulations,
-	curve::Curve,
-	curve_plot::CurvePlot,
+	curve::{Curve, Interpolation},
+	curve_plot::{CurvePlot, AxisScale},
+	curve_comparison::{CurveComparison, Weighting},
+	curve_set::{CurveSet, CurveMetadata},
+	checkpoint,
+	grid::SizeGrid,
+	latency::{self, LatencyArgs},
 };
 
 const BATCH_SIZE: usize = 10_000_000;
+const DEFAULT_CHECKPOINT_INTERVAL: u64 = 100_000_000;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -59,20 +57,76 @@ struct Args {
 	#[arg(short, long)]
 	shards_s: Option<u32>,
 
-	#[arg(short, long)]
-	kosmo_policy: Option<KosmoPolicy>,
+	/// The Kosmo policies (comma-separated), which are run in one pass
+	/// over the trace.
+	#[arg(short, long, value_delimiter = ',')]
+	kosmo_policy: Vec<NamedPolicy<KosmoPolicy>>,
 
-	#[arg(short, long)]
-	minisim_policy: Option<CachePolicy>,
+	/// The MiniSim policies (comma-separated), which are run in one pass
+	/// over the trace.
+	#[arg(short, long, value_delimiter = ',')]
+	minisim_policy: Vec<NamedPolicy<CachePolicy>>,
 
+	/// The output path of the plot (saved as SVG or PNG if the path ends in
+	/// .svg or .png, and as PDF otherwise).
 	#[arg(short, long)]
 	output: String,
 
+	/// The scale of the x axis of the plot (linear or log).
+	#[arg(long, default_value = "linear")]
+	x_scale: AxisScale,
+
+	/// The range of sizes of the x axis of the plot.
+	#[arg(long, num_args = 2, value_names = ["MIN", "MAX"])]
+	x_range: Option<Vec<u64>>,
+
+	/// The range of miss ratios of the y axis of the plot.
+	#[arg(long, num_args = 2, value_names = ["MIN", "MAX"])]
+	y_range: Option<Vec<f64>>,
+
+	/// Adds a subplot of the absolute error of each curve compared to the
+	/// accurate curve.
+	#[arg(long, requires = "accurate_path")]
+	error_plot: bool,
+
+	/// The file to which the curves are saved along with the configuration
+	/// of the run (as JSON if the path ends in .json, and as CSV otherwise).
+	#[arg(short, long)]
+	curve_output: Option<String>,
+
+	#[command(flatten)]
+	latency: LatencyArgs,
+
 	#[arg(short, long)]
 	accurate_path: Option<String>,
 
 	#[arg(short, long)]
 	run_type: RunType,
+
+	/// The cache sizes simulated by MiniSim (linear[-<points>],
+	/// log[-<points>[-<min size>]], or a path to a file with one size per
+	/// line).
+	#[arg(short, long, default_value = "linear")]
+	grid: SizeGrid,
+
+	/// The interpolation between the points of the curves (step or
+	/// linear), which is used for the MAE and the plot.
+	#[arg(short, long, default_value = "step")]
+	interpolation: Interpolation,
+
+	/// The file to which the state is periodically saved (every policy must
+	/// support checkpoints).
+	#[arg(long)]
+	checkpoint_path: Option<String>,
+
+	/// The number of accesses between checkpoints.
+	#[arg(long, default_value_t = DEFAULT_CHECKPOINT_INTERVAL)]
+	checkpoint_interval: u64,
+
+	/// Restores the state from the checkpoint file and continues reading
+	/// the trace from where it left off.
+	#[arg(long, requires = "checkpoint_path")]
+	resume: bool,
 }
 
 #[derive(Clone, PartialEq, ValueEnum)]
@@ -81,17 +135,37 @@ enum RunType {
 	Throughput,
 }
 
+/// A policy along with the name it was parsed from, which labels its
+/// curve.
+#[derive(Clone)]
+struct NamedPolicy<T> {
+	name: String,
+	policy: T,
+}
+
 fn main() {
 	let args = Args::parse();
 
-	let mut algorithm = match (&args.kosmo_policy, &args.minisim_policy) {
-		(Some(_), None) => init_kosmo(&args),
-		(None, Some(_)) => init_minisimulations(&args),
-		(Some(_), Some(_)) => panic!("You may not configure both Kosmo and MiniSim simultaneously."),
-		(None, None) => panic!("You must configure at one of Kosmo or MiniSim."),
+	// the plot is configured before the run, so that invalid plot options
+	// are reported before the trace is processed
+	let mut curve_plot = init_curve_plot(&args);
+
+	let mut algorithm = match (args.kosmo_policy.is_empty(), args.minisim_policy.is_empty()) {
+		(false, true) => init_kosmo(&args),
+		(true, false) => init_minisimulations(&args),
+		(false, false) => panic!("You may not configure both Kosmo and MiniSim simultaneously."),
+		(true, true) => panic!("You must configure at one of Kosmo or MiniSim."),
 	};
 
-	let reader = BinaryReader::<Access>::from_path(&args.path)
+	// a checkpoint is only saved after part of the trace is processed, so
+	// policies which cannot be saved are reported before the run starts
+	if args.checkpoint_path.is_some() {
+		algorithm
+			.save(&mut io::sink())
+			.unwrap_or_else(|err| Args::command().error(ErrorKind::ArgumentConflict, err).exit());
+	}
+
+	let mut reader = BinaryReader::<Access>::from_path(&args.path)
 		.expect("Invalid trace path.");
 
 	println!("{}", args.path);
@@ -105,6 +179,20 @@ fn main() {
 		mem::clear(None).expect("Could not clear memory refs.");
 	}
 
+	let mut offset: u64 = 0;
+
+	if args.resume {
+		let checkpoint_path = args.checkpoint_path.as_ref().unwrap();
+
+		offset = checkpoint::restore_checkpoint(checkpoint_path, algorithm.as_mut())
+			.expect("Could not restore checkpoint.");
+
+		// skip the accesses which were processed before the checkpoint
+		for _ in reader.by_ref().take(offset as usize) {
+			progress.tick(Access::chunk_size());
+		}
+	}
+
 	let mut accesses: Option<Vec<Access>> = match args.run_type {
 		RunType::Throughput => Some(Vec::<Access>::new()),
 		_ => None,
@@ -113,7 +201,7 @@ fn main() {
 	let mut total_time: u64 = 0;
 	let mut total_accesses: u64 = 0;
 
-	for access in reader {
+	for access in reader.by_ref() {
 		match accesses.as_mut() {
 			Some(accesses) if accesses.len() == BATCH_SIZE => {
 				total_time += run_batch(&mut algorithm, accesses);
@@ -126,35 +214,78 @@ fn main() {
 
 		progress.tick(Access::chunk_size());
 		total_accesses += 1;
-	}
+		offset += 1;
+
+		if let Some(checkpoint_path) = &args.checkpoint_path &&
+			offset.is_multiple_of(args.checkpoint_interval)
+		{
+			// the accesses waiting in the batch are processed first, so that
+			// the checkpoint covers every access which has been read
+			if let Some(accesses) = accesses.as_mut() {
+				total_time += run_batch(&mut algorithm, accesses);
+				accesses.clear();
+			}
 
-	if let Some(accesses) = accesses.as_mut() {
-		if !accesses.is_empty() {
-			total_time += run_batch(&mut algorithm, accesses);
+			checkpoint::save_checkpoint(checkpoint_path, offset, algorithm.as_ref())
+				.expect("Could not save checkpoint.");
 		}
 	}
 
-	let accurate_curve = args.accurate_path.map(|path| {
-		Curve::from_file(&path)
+	if let Some(accesses) = accesses.as_mut() && !accesses.is_empty() {
+		total_time += run_batch(&mut algorithm, accesses);
+	}
+
+	let accurate_curve = args.accurate_path.as_ref().map(|path| {
+		Curve::from_file(path)
 			.expect("Could not find accurate curve.")
 	});
 
-	let mut figure = Figure::default();
-	let mut curve_plot = CurvePlot::default();
-
-	let curve = algorithm.curve();
-
 	if let Some(accurate_curve) = &accurate_curve {
-		println!("MAE: {}", accurate_curve.mae(&curve));
+		curve_plot.set_reference(accurate_curve.clone(), "Accurate");
 	}
 
-	let algorithm_id = if args.kosmo_policy.is_some() {
-		"Kosmo"
+	let (algorithm_id, policy_names) = if !args.kosmo_policy.is_empty() {
+		("Kosmo", get_policy_names(&args.kosmo_policy))
 	} else {
-		"MiniSim"
+		("MiniSim", get_policy_names(&args.minisim_policy))
 	};
 
-	curve_plot.add(curve, Some(algorithm_id));
+	let mut curve_set = CurveSet::new(init_metadata(&args, algorithm_id, &policy_names));
+	let latency_model = args.latency.model();
+	let mut performances = Vec::new();
+
+	for (curve, policy_name) in algorithm.curves().into_iter().zip(&policy_names) {
+		// a single policy is labelled as before, by the algorithm alone
+		let label = match policy_names.len() {
+			1 => algorithm_id.to_string(),
+			_ => format!("{algorithm_id} ({policy_name})"),
+		};
+
+		if let Some(accurate_curve) = &accurate_curve {
+			print_errors(&label, accurate_curve, &curve, args.interpolation);
+		}
+
+		if let Some(latency_model) = &latency_model {
+			performances.push((
+				*policy_name,
+				latency_model.performances(&curve, args.interpolation),
+			));
+		}
+
+		curve_set.add(policy_name, curve.clone());
+		curve_plot.add(curve, Some(label.as_str()));
+	}
+
+	if let Some(curve_output) = &args.curve_output {
+		curve_set
+			.to_file(curve_output)
+			.expect("Could not save curves.");
+	}
+
+	if let Some(latency_output) = &args.latency.latency_output {
+		latency::to_file(latency_output, &performances)
+			.expect("Could not save latencies.");
+	}
 
 	match args.run_type {
 		RunType::Memory => {
@@ -177,10 +308,8 @@ fn main() {
 		},
 	}
 
-	figure.add(curve_plot.to_plot());
-
-	figure
-		.save(&args.output)
+	curve_plot
+		.save(&args.output, args.interpolation)
 		.expect("Could not save figure.");
 }
 
@@ -194,18 +323,77 @@ fn run_batch(algorithm: &mut Box<dyn Algorithm>, accesses: &[Access]) -> u64 {
 	start_time.elapsed().as_millis() as u64
 }
 
+fn print_errors(
+	label: &str,
+	accurate_curve: &Curve,
+	curve: &Curve,
+	interpolation: Interpolation,
+) {
+	let comparison = CurveComparison::new().with_interpolation(interpolation);
+
+	let errors = comparison.compare(accurate_curve, curve);
+
+	let weighted_errors = comparison
+		.with_weighting(Weighting::InverseSize)
+		.compare(accurate_curve, curve);
+
+	println!("MAE ({label}): {}", errors.mae);
+	println!("RMSE ({label}): {}", errors.rmse);
+	println!("Max error ({label}): {} at {}", errors.max_error, fmt::memory(errors.max_error_size, Some(2)));
+	println!("Area between curves ({label}): {}", errors.area);
+	println!("Small-cache weighted MAE ({label}): {}", weighted_errors.mae);
+}
+
+fn init_curve_plot(args: &Args) -> CurvePlot {
+	let mut curve_plot = CurvePlot::default();
+
+	curve_plot.set_x_scale(args.x_scale);
+	curve_plot.set_error_plot(args.error_plot);
+
+	if let Some(x_range) = &args.x_range {
+		curve_plot
+			.set_x_range(x_range[0], x_range[1])
+			.unwrap_or_else(|err| Args::command().error(ErrorKind::ValueValidation, err).exit());
+	}
+
+	if let Some(y_range) = &args.y_range {
+		curve_plot
+			.set_y_range(y_range[0], y_range[1])
+			.unwrap_or_else(|err| Args::command().error(ErrorKind::ValueValidation, err).exit());
+	}
+
+	curve_plot
+}
+
+fn init_metadata(args: &Args, algorithm_id: &str, policy_names: &[&str]) -> CurveMetadata {
+	let granularity = match args.kosmo_policy.is_empty() {
+		true => None,
+		false => Some(kosmo::GRANULARITY),
+	};
+
+	CurveMetadata {
+		trace_path: Some(args.path.clone()),
+		algorithm: Some(algorithm_id.to_string()),
+		policy: Some(policy_names.join(",")),
+		shards_t: args.shards_t,
+		shards_s_max: args.shards_s,
+		granularity,
+		..CurveMetadata::new()
+	}
+}
+
 fn init_kosmo(args: &Args) -> Box<dyn Algorithm> {
-	let policy = args.kosmo_policy.as_ref().unwrap().clone();
+	let policies = get_policies(&args.kosmo_policy);
 	let shards = init_shards(args);
 
-	Box::new(Kosmo::new(&[policy], shards))
+	Box::new(Kosmo::new(&policies, shards))
 }
 
 fn init_minisimulations(args: &Args) -> Box<dyn Algorithm> {
-	let policy = args.minisim_policy.as_ref().unwrap();
+	let policies = get_policies(&args.minisim_policy);
 	let shards = init_shards(args);
 
-	Box::new(Minisimulations::new(policy, args.wss, shards))
+	Box::new(Minisimulations::with_grid(&policies, args.wss, &args.grid, shards))
 }
 
 fn init_shards(args: &Args) -> Option<Box<dyn Shards>> {
@@ -216,3 +404,31 @@ fn init_shards(args: &Args) -> Option<Box<dyn Shards>> {
 		(None, None) => None,
 	}
 }
+
+fn get_policies<T: Clone>(named_policies: &[NamedPolicy<T>]) -> Vec<T> {
+	named_policies
+		.iter()
+		.map(|named_policy| named_policy.policy.clone())
+		.collect()
+}
+
+fn get_policy_names<T>(named_policies: &[NamedPolicy<T>]) -> Vec<&str> {
+	named_policies
+		.iter()
+		.map(|named_policy| named_policy.name.as_str())
+		.collect()
+}
+
+impl<T> FromStr for NamedPolicy<T>
+where
+	T: FromStr<Err = Error>,
+{
+	type Err = Error;
+
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		Ok(NamedPolicy {
+			name: value.to_string(),
+			policy: T::from_str(value)?,
+		})
+	}
+}
@@ -0,0 +1,268 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::{
+	collections::BTreeSet,
+	sync::Arc,
+};
+
+use rustc_hash::FxHashMap;
+
+use crate::{
+	cache::Cache,
+	access::{Access, Key, Size, Timestamp},
+};
+
+// the number of objects with the furthest next uses which are considered
+// for eviction when objects have different sizes
+const EVICTION_CANDIDATES: usize = 64;
+
+/// Belady's offline optimal (MIN) cache. The next use of every access must
+/// be computed ahead of time (see `OptCache::next_uses`), and each access's
+/// timestamp must be its (one-based) position in the trace.
+///
+/// When all objects have the same size, the object whose next use is the
+/// furthest in the future is evicted, which is optimal. Otherwise, the
+/// object with the largest product of size and time until its next use,
+/// among those with the furthest next uses, is evicted, which is an
+/// approximation. In both cases, an object may bypass the cache if it is
+/// itself the best eviction candidate.
+pub struct OptCache {
+	max_size: u64,
+	current_size: u64,
+
+	next_uses: Arc<Vec<Timestamp>>,
+	timestamp: Timestamp,
+
+	count: f64,
+	hits: f64,
+
+	map: FxHashMap<Key, OptObject>,
+	queue: BTreeSet<(Timestamp, Key)>,
+}
+
+struct OptObject {
+	size: Size,
+	next_use: Timestamp,
+}
+
+impl OptCache {
+	pub fn new(size: u64, next_uses: Arc<Vec<Timestamp>>) -> Self {
+		OptCache {
+			max_size: size,
+			current_size: 0,
+
+			next_uses,
+			timestamp: 0,
+
+			count: 0.0,
+			hits: 0.0,
+
+			map: FxHashMap::default(),
+			queue: BTreeSet::new(),
+		}
+	}
+
+	/// Returns the timestamp of the next access to the same key for each
+	/// access in the supplied keys, or `Timestamp::MAX` if the key is not
+	/// accessed again.
+	pub fn next_uses(keys: &[Key]) -> Vec<Timestamp> {
+		let mut next_uses = vec![Timestamp::MAX; keys.len()];
+		let mut last_uses = FxHashMap::<Key, Timestamp>::default();
+
+		for (index, key) in keys.iter().enumerate().rev() {
+			if let Some(timestamp) = last_uses.insert(*key, index as Timestamp + 1) {
+				next_uses[index] = timestamp;
+			}
+		}
+
+		next_uses
+	}
+}
+
+impl Cache for OptCache {
+	fn size(&self) -> u64 {
+		self.max_size
+	}
+
+	fn miss_ratio(&self) -> f64 {
+		if self.count > 0.0 {
+			return 1.0 - self.hits / self.count;
+		}
+
+		0.0
+	}
+
+	fn increment_count(&mut self) {
+		self.count += 1.0
+	}
+
+	fn increment_hits(&mut self) {
+		self.hits += 1.0
+	}
+
+	fn clear_counters(&mut self) {
+		self.count = 0.0;
+		self.hits = 0.0;
+	}
+
+	fn process_get(&mut self, access: &Access) -> bool {
+		let next_use = self.next_use(access);
+
+		let Some(opt_object) = self.map.get_mut(&access.key) else {
+			return false;
+		};
+
+		self.queue.remove(&(opt_object.next_use, access.key));
+		self.queue.insert((next_use, access.key));
+
+		opt_object.next_use = next_use;
+
+		true
+	}
+
+	fn process_set(&mut self, access: &Access) {
+		if access.size as u64 > self.max_size || self.has(access.key) {
+			return;
+		}
+
+		let next_use = self.next_use(access);
+
+		self.map.insert(access.key, OptObject {
+			size: access.size,
+			next_use,
+		});
+
+		self.queue.insert((next_use, access.key));
+		self.current_size += access.size as u64;
+
+		// the new object is inserted before evicting so that it may be
+		// evicted itself (i.e., bypass the cache)
+		self.reduce(self.max_size);
+	}
+
+	fn process_del(&mut self, key: Key) {
+		let Some(opt_object) = self.map.remove(&key) else {
+			return;
+		};
+
+		self.queue.remove(&(opt_object.next_use, key));
+		self.current_size -= opt_object.size as u64;
+	}
+
+	fn process_has(&self, key: Key) -> bool {
+		self.map.contains_key(&key)
+	}
+
+	fn reduce(&mut self, target_size: u64) {
+		while self.current_size > target_size {
+			let Some(key) = self.eviction_candidate() else {
+				return;
+			};
+
+			self.process_del(key);
+		}
+	}
+
+	fn resize(&mut self, size: u64) {
+		self.reduce(size);
+		self.max_size = size;
+	}
+
+	fn rescale(&mut self, ratio: f64) {
+		self.count *= ratio;
+		self.hits *= ratio;
+	}
+}
+
+impl OptCache {
+	fn next_use(&mut self, access: &Access) -> Timestamp {
+		self.timestamp = access.timestamp;
+
+		self.next_uses
+			.get(access.timestamp as usize - 1)
+			.copied()
+			.unwrap_or(Timestamp::MAX)
+	}
+
+	/// Returns the key of the object with the largest product of size and
+	/// time until its next use, among the objects with the furthest next
+	/// uses.
+	fn eviction_candidate(&self) -> Option<Key> {
+		self.queue
+			.iter()
+			.rev()
+			.take(EVICTION_CANDIDATES)
+			.max_by_key(|(next_use, key)| {
+				let size = self.map[key].size as u128;
+				let distance = next_use.saturating_sub(self.timestamp) as u128;
+
+				// ties are broken in favour of the furthest next use
+				(size * distance, *next_use)
+			})
+			.map(|(_, key)| *key)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use crate::{
+		access::{Access, Command, Key},
+		cache::{Cache, LruCache},
+		opt::OptCache,
+	};
+
+	fn trace(keys: &[Key]) -> Vec<Access> {
+		keys.iter()
+			.enumerate()
+			.map(|(index, key)| Access {
+				timestamp: index as u64 + 1,
+				command: Command::Get,
+				key: *key,
+				size: 1,
+				ttl: None,
+			})
+			.collect()
+	}
+
+	fn misses(cache: &mut dyn Cache, trace: &[Access]) -> usize {
+		trace.iter()
+			.filter(|access| !cache.handle_self_populating(access))
+			.count()
+	}
+
+	#[test]
+	fn next_uses_are_correct() {
+		let next_uses = OptCache::next_uses(&[0, 1, 0, 2, 1]);
+		assert_eq!(next_uses, vec![3, 5, u64::MAX, u64::MAX, u64::MAX]);
+	}
+
+	#[test]
+	fn misses_are_optimal() {
+		let keys = [1, 2, 3, 4, 1, 2, 5, 1, 2, 3, 4, 5];
+		let trace = trace(&keys);
+
+		let mut cache = OptCache::new(3, Arc::new(OptCache::next_uses(&keys)));
+
+		// MIN incurs 7 misses on this trace with 3 slots, with the cold
+		// misses to keys 4 and 5 bypassing the cache
+		assert_eq!(misses(&mut cache, &trace), 7);
+	}
+
+	#[test]
+	fn opt_beats_lru() {
+		let keys = (0..1_000).map(|index| index % 120).collect::<Vec<Key>>();
+		let trace = trace(&keys);
+
+		let mut opt_cache = OptCache::new(100, Arc::new(OptCache::next_uses(&keys)));
+		let mut lru_cache = LruCache::new(100);
+
+		assert!(misses(&mut opt_cache, &trace) < misses(&mut lru_cache, &trace));
+	}
+}
@@ -0,0 +1,169 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use std::io::{Error, ErrorKind};
+
+use crate::{
+	access::Timestamp,
+	cache::GdsCost,
+};
+
+const DEFAULT_S3_FIFO_SMALL_RATIO: f64 = 0.1;
+const DEFAULT_S3_FIFO_GHOST_RATIO: f64 = 0.9;
+const DEFAULT_LIRS_HIR_RATIO: f64 = 0.01;
+const DEFAULT_LRU_K_CORRELATED_PERIOD: Timestamp = 0;
+const DEFAULT_SLRU_RATIOS: [f64; 2] = [0.2, 0.8];
+
+/// Parses `s3fifo[-<small ratio>[-<ghost ratio>]]` into the small and
+/// ghost ratios.
+pub(crate) fn parse_s3_fifo_config(value: &str) -> Result<(f64, f64), Error> {
+	let values = value
+		.split('-')
+		.skip(1)
+		.collect::<Vec<&str>>();
+
+	if value != "s3fifo" && !value.starts_with("s3fifo-") || values.len() > 2 {
+		return Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid S3-FIFO policy config."
+		));
+	}
+
+	let Ok(small_ratio) = values.first().map_or(Ok(DEFAULT_S3_FIFO_SMALL_RATIO), |value| value.parse::<f64>()) else {
+		return Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid S3-FIFO policy config small ratio value."
+		));
+	};
+
+	let Ok(ghost_ratio) = values.get(1).map_or(Ok(DEFAULT_S3_FIFO_GHOST_RATIO), |value| value.parse::<f64>()) else {
+		return Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid S3-FIFO policy config ghost ratio value."
+		));
+	};
+
+	if small_ratio <= 0.0 || small_ratio >= 1.0 || ghost_ratio < 0.0 {
+		return Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid S3-FIFO policy config ratio."
+		));
+	}
+
+	Ok((small_ratio, ghost_ratio))
+}
+
+/// Parses `lirs[-<HIR ratio>]` into the HIR ratio.
+pub(crate) fn parse_lirs_config(value: &str) -> Result<f64, Error> {
+	let hir_ratio = match value {
+		"lirs" => Ok(DEFAULT_LIRS_HIR_RATIO),
+		value => value
+			.strip_prefix("lirs-")
+			.and_then(|value| value.parse::<f64>().ok())
+			.ok_or(Error::new(
+				ErrorKind::InvalidData,
+				"Invalid LIRS policy config HIR ratio value."
+			)),
+	}?;
+
+	if hir_ratio <= 0.0 || hir_ratio >= 1.0 {
+		return Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid LIRS policy config HIR ratio."
+		));
+	}
+
+	Ok(hir_ratio)
+}
+
+/// Parses `gds[-<cost>]` or `gdsf[-<cost>]` into whether the frequency
+/// of objects is used (i.e., GDSF) and the cost.
+pub(crate) fn parse_gds_config(value: &str) -> Result<(bool, GdsCost), Error> {
+	let (policy, cost) = match value.split_once('-') {
+		Some((policy, cost)) => (policy, cost.parse::<GdsCost>()?),
+		None => (value, GdsCost::Uniform),
+	};
+
+	match policy {
+		"gds" => Ok((false, cost)),
+		"gdsf" => Ok((true, cost)),
+
+		_ => Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid GreedyDual-Size policy config."
+		)),
+	}
+}
+
+/// Parses `lruk-<K>[-<correlated reference period>]` into K and the
+/// correlated reference period.
+pub(crate) fn parse_lru_k_config(value: &str) -> Result<(usize, Timestamp), Error> {
+	let values = value
+		.split('-')
+		.skip(1)
+		.collect::<Vec<&str>>();
+
+	if !value.starts_with("lruk-") || values.len() > 2 {
+		return Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid LRU-K policy config."
+		));
+	}
+
+	let Ok(k) = values[0].parse::<usize>() else {
+		return Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid LRU-K policy config K value."
+		));
+	};
+
+	let Ok(correlated_period) = values.get(1).map_or(Ok(DEFAULT_LRU_K_CORRELATED_PERIOD), |value| value.parse::<Timestamp>()) else {
+		return Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid LRU-K policy config correlated reference period value."
+		));
+	};
+
+	if k == 0 {
+		return Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid LRU-K policy config K."
+		));
+	}
+
+	Ok((k, correlated_period))
+}
+
+/// Parses `slru[-<ratio>...]` into the ratios of the segments.
+pub(crate) fn parse_slru_config(value: &str) -> Result<Vec<f64>, Error> {
+	if value == "slru" {
+		return Ok(DEFAULT_SLRU_RATIOS.to_vec());
+	}
+
+	let Some(values) = value.strip_prefix("slru-") else {
+		return Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid SLRU policy config."
+		));
+	};
+
+	let Ok(ratios) = values.split('-').map(|value| value.parse::<f64>()).collect::<Result<Vec<f64>, _>>() else {
+		return Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid SLRU policy config segment ratio value."
+		));
+	};
+
+	if ratios.iter().any(|ratio| *ratio <= 0.0) || (ratios.iter().sum::<f64>() - 1.0).abs() > 1e-6 {
+		return Err(Error::new(
+			ErrorKind::InvalidData,
+			"Invalid SLRU policy config segment ratios (they must be positive and sum to one)."
+		));
+	}
+
+	Ok(ratios)
+}
@@ -8,6 +8,8 @@
 mod fixed_rate;
 mod fixed_size;
 
+use std::io::{Error, Read, Write};
+
 use fasthash::murmur3;
 use crate::access::{Access, Key};
 
@@ -49,6 +51,12 @@ pub trait Shards {
 	fn get_removal(&mut self) -> Option<Key> {
 		None
 	}
+
+	/// Writes the state of the sampler to a checkpoint.
+	fn save(&self, _: &mut dyn Write) -> Result<(), Error>;
+
+	/// Restores the state of the sampler from a checkpoint.
+	fn restore(&mut self, _: &mut dyn Read) -> Result<(), Error>;
 }
 
 fn hash(key: Key) -> u128 {
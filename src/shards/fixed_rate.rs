@@ -5,11 +5,17 @@
  * LICENSE file in the root directory of this source tree.
  */
 
+use std::io::{Error, Read, Write};
+
+use serde::{Serialize, Deserialize};
+
 use crate::{
 	shards::Shards,
 	access::Access,
+	checkpoint,
 };
 
+#[derive(Serialize, Deserialize)]
 pub struct ShardsFixedRate {
 	global_t: u64,
 
@@ -57,4 +63,13 @@ impl Shards for ShardsFixedRate {
 
 		true
 	}
+
+	fn save(&self, writer: &mut dyn Write) -> Result<(), Error> {
+		checkpoint::save(writer, self)
+	}
+
+	fn restore(&mut self, reader: &mut dyn Read) -> Result<(), Error> {
+		*self = checkpoint::restore(reader)?;
+		Ok(())
+	}
 }
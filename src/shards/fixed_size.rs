@@ -6,17 +6,21 @@
  */
 
 use std::{
+	io::{Error, Read, Write},
 	collections::BTreeSet,
 	cmp::{Ord, Ordering},
 };
 
 use rustc_hash::FxHashSet;
+use serde::{Serialize, Deserialize};
 
 use crate::{
 	shards::Shards,
 	access::{Access, Key},
+	checkpoint,
 };
 
+#[derive(Serialize, Deserialize)]
 pub struct ShardsFixedSize {
 	global_t: u64,
 	s_max: u32,
@@ -29,6 +33,7 @@ pub struct ShardsFixedSize {
 	keys: FxHashSet<Key>,
 }
 
+#[derive(Serialize, Deserialize)]
 struct Entry {
 	key: Key,
 	t: u64,
@@ -108,6 +113,15 @@ impl Shards for ShardsFixedSize {
 
 		None
 	}
+
+	fn save(&self, writer: &mut dyn Write) -> Result<(), Error> {
+		checkpoint::save(writer, self)
+	}
+
+	fn restore(&mut self, reader: &mut dyn Read) -> Result<(), Error> {
+		*self = checkpoint::restore(reader)?;
+		Ok(())
+	}
 }
 
 impl Entry {
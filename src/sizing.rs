@@ -0,0 +1,133 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+use clap::{Parser, Subcommand};
+use kwik::fmt;
+
+use kosmo_fast24::{
+	curve::{Curve, Interpolation},
+	grid::SizeGrid,
+};
+
+// the extra cache is in decimal GB by default, as in the cost model
+const GB: u64 = 1_000_000_000;
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+	/// The path to the curve file.
+	#[arg(short, long)]
+	path: String,
+
+	/// The interpolation between the points of the curve (step or
+	/// linear).
+	#[arg(short, long, default_value = "step")]
+	interpolation: Interpolation,
+
+	#[command(subcommand)]
+	command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+	/// Reports the smallest size which reaches each target miss ratio.
+	Target {
+		/// The target miss ratios.
+		#[arg(num_args = 1.., required = true)]
+		miss_ratios: Vec<f64>,
+	},
+
+	/// Reports the knee of the curve.
+	Knee,
+
+	/// Reports the increase in hit ratio from extra cache at each size.
+	Gain {
+		/// The cache sizes (in bytes).
+		#[arg(num_args = 1.., required = true)]
+		sizes: Vec<u64>,
+
+		/// The extra cache (in bytes, 1 GB of 10^9 bytes by default).
+		#[arg(short, long, default_value_t = GB)]
+		extra_size: u64,
+	},
+
+	/// Reports the miss ratio and the increase in hit ratio from extra
+	/// cache at each size of a grid.
+	Table {
+		/// The sizes of the table (linear[-<points>], log[-<points>[-<min
+		/// size>]], or a path to a file with one size per line).
+		#[arg(short, long, default_value = "linear-10")]
+		grid: SizeGrid,
+
+		/// The extra cache (in bytes, 1 GB of 10^9 bytes by default).
+		#[arg(short, long, default_value_t = GB)]
+		extra_size: u64,
+	},
+}
+
+fn main() {
+	let args = Args::parse();
+
+	let curve = Curve::from_file(&args.path)
+		.expect("Could not load curve.");
+
+	match &args.command {
+		Command::Target { miss_ratios } => {
+			for miss_ratio in miss_ratios {
+				match curve.get_size_for_miss_ratio(*miss_ratio, args.interpolation) {
+					Some(size) => println!(
+						"Miss ratio {miss_ratio}: {} ({size} B)",
+						fmt::memory(size, Some(2)),
+					),
+
+					None => println!("Miss ratio {miss_ratio}: not reached"),
+				}
+			}
+		},
+
+		Command::Knee => match curve.get_knee() {
+			Some(size) => println!(
+				"Knee: {} ({size} B), miss ratio {}",
+				fmt::memory(size, Some(2)),
+				curve.get_interpolated_miss_ratio(size, args.interpolation),
+			),
+
+			None => println!("The curve has no knee."),
+		},
+
+		Command::Gain { sizes, extra_size } => {
+			for size in sizes {
+				println!(
+					"{} ({size} B): +{} hit ratio per {} ({extra_size} B) of extra cache",
+					fmt::memory(*size, Some(2)),
+					curve.get_marginal_hit_gain(*size, *extra_size, args.interpolation),
+					fmt::memory(*extra_size, Some(2)),
+				);
+			}
+		},
+
+		Command::Table { grid, extra_size } => {
+			println!(
+				"{:>14}  {:>16}  {:>10}  {:>14}",
+				"Size",
+				"Size (B)",
+				"Miss ratio",
+				format!("Gain/{}", fmt::memory(*extra_size, Some(0))),
+			);
+
+			for size in grid.sizes(curve.get_max_size()) {
+				println!(
+					"{:>14}  {:>16}  {:>10.6}  {:>14.6}",
+					fmt::memory(size, Some(2)),
+					size,
+					curve.get_interpolated_miss_ratio(size, args.interpolation),
+					curve.get_marginal_hit_gain(size, *extra_size, args.interpolation),
+				);
+			}
+		},
+	}
+}
@@ -0,0 +1,13 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Helpers shared by the unit tests.
+
+/// Asserts that two floating point values are equal up to rounding.
+pub(crate) fn assert_close(actual: f64, expected: f64) {
+	assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+}
@@ -5,13 +5,9 @@
  * LICENSE file in the root directory of this source tree.
  */
 
-#![feature(btree_cursors)]
-
-mod access;
-
 use rustc_hash::FxHashSet;
 use clap::Parser;
-use crate::access::{Access, Key};
+use kosmo_fast24::access::{Access, Key};
 
 use kwik::{
 	file::{
@@ -0,0 +1,222 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Property tests of every `Cache` implementation. Each test runs random
+//! sequences of operations (get, set, del, reduce and resize) on a cache
+//! and checks its invariants after every operation against a naive model
+//! which tracks the resident objects and the cache's counters.
+
+use std::str::FromStr;
+
+use proptest::prelude::*;
+use rustc_hash::FxHashMap;
+
+use kosmo_fast24::{
+	access::{Access, Command, Key, Size},
+	cache::{Cache, CachePolicy},
+};
+
+const MAX_NUM_KEYS: Key = 32;
+const MAX_OBJECT_SIZE: Size = 64;
+const MAX_CACHE_SIZE: u64 = 512;
+const MAX_NUM_OPERATIONS: usize = 300;
+
+#[derive(Debug, Clone)]
+enum Operation {
+	Get(Key, Size),
+	Set(Key, Size),
+	Del(Key),
+	Reduce(u64),
+	Resize(u64),
+}
+
+/// The naive model of a cache. It tracks the resident objects itself,
+/// adding those which are set and removing those which are deleted, and
+/// decides whether each get is a hit. Since it does not know which objects
+/// the policy evicts, it drops the objects which the cache no longer has,
+/// but it never adds one.
+#[derive(Default)]
+struct Model {
+	objects: FxHashMap<Key, Size>,
+
+	count: u64,
+	hits: u64,
+}
+
+impl Model {
+	fn resident_size(&self) -> u64 {
+		self.objects
+			.values()
+			.map(|size| *size as u64)
+			.sum()
+	}
+
+	/// Drops the objects which the cache has evicted, checking that the
+	/// cache has no object which the model does not.
+	fn evict(&mut self, cache: &dyn Cache) -> Result<(), TestCaseError> {
+		self.objects.retain(|key, _| cache.has(*key));
+
+		for key in 0..MAX_NUM_KEYS {
+			prop_assert!(
+				!cache.has(key) || self.objects.contains_key(&key),
+				"key {} is resident but was not set",
+				key,
+			);
+		}
+
+		Ok(())
+	}
+
+	fn miss_ratio(&self) -> f64 {
+		if self.count > 0 {
+			return 1.0 - self.hits as f64 / self.count as f64;
+		}
+
+		0.0
+	}
+}
+
+fn operation() -> impl Strategy<Value = Operation> {
+	prop_oneof![
+		4 => (0..MAX_NUM_KEYS, 1..=MAX_OBJECT_SIZE).prop_map(|(key, size)| Operation::Get(key, size)),
+		4 => (0..MAX_NUM_KEYS, 1..=MAX_OBJECT_SIZE).prop_map(|(key, size)| Operation::Set(key, size)),
+		1 => (0..MAX_NUM_KEYS).prop_map(Operation::Del),
+		1 => (0..=MAX_CACHE_SIZE).prop_map(Operation::Reduce),
+		1 => (0..=MAX_CACHE_SIZE).prop_map(Operation::Resize),
+	]
+}
+
+fn to_access(timestamp: u64, key: Key, size: Size) -> Access {
+	Access {
+		timestamp,
+		command: Command::Get,
+		key,
+		size,
+		ttl: None,
+	}
+}
+
+fn check_policy(
+	name: &str,
+	size: u64,
+	operations: &[Operation],
+) -> Result<(), TestCaseError> {
+	let policy = CachePolicy::from_str(name).unwrap();
+
+	let mut cache = policy.new_cache(size);
+	let mut model = Model::default();
+
+	for (index, operation) in operations.iter().enumerate() {
+		let timestamp = index as u64 + 1;
+
+		match *operation {
+			Operation::Get(key, object_size) => {
+				let expected_hit = model.objects.contains_key(&key);
+				let hit = cache.get(&to_access(timestamp, key, object_size));
+
+				if object_size as u64 <= cache.size() {
+					model.count += 1;
+					model.hits += expected_hit as u64;
+
+					prop_assert_eq!(hit, expected_hit, "get disagrees with the model for key {}", key);
+				} else {
+					prop_assert!(!hit, "get hit an object larger than the cache");
+				}
+			},
+
+			Operation::Set(key, object_size) => {
+				// a set of a resident object is ignored, and an object larger
+				// than the cache is not inserted
+				if object_size as u64 <= cache.size() {
+					model.objects.entry(key).or_insert(object_size);
+				}
+
+				cache.set(&to_access(timestamp, key, object_size));
+			},
+
+			Operation::Del(key) => {
+				model.objects.remove(&key);
+
+				cache.del(key);
+				prop_assert!(!cache.has(key), "del did not remove key {}", key);
+			},
+
+			Operation::Reduce(target_size) => {
+				let target_size = target_size.min(cache.size());
+
+				cache.reduce(target_size);
+				model.evict(cache.as_ref())?;
+
+				prop_assert!(
+					model.resident_size() <= target_size,
+					"reduce did not shrink the cache to {} bytes",
+					target_size,
+				);
+			},
+
+			Operation::Resize(new_size) => {
+				cache.resize(new_size);
+				model.evict(cache.as_ref())?;
+
+				prop_assert_eq!(cache.size(), new_size);
+				prop_assert!(
+					model.resident_size() <= new_size,
+					"resize did not shrink the cache to {} bytes",
+					new_size,
+				);
+			},
+		}
+
+		model.evict(cache.as_ref())?;
+
+		prop_assert!(
+			model.resident_size() <= cache.size(),
+			"the resident objects exceed the cache size after {:?}",
+			operation,
+		);
+
+		prop_assert!((cache.miss_ratio() - model.miss_ratio()).abs() < 1e-9);
+	}
+
+	Ok(())
+}
+
+macro_rules! cache_property_tests {
+	($($test:ident => $policy:expr),* $(,)?) => {
+		proptest! {
+			$(
+				#[test]
+				fn $test(
+					size in 0..=MAX_CACHE_SIZE,
+					operations in prop::collection::vec(operation(), 0..MAX_NUM_OPERATIONS),
+				) {
+					check_policy($policy, size, &operations)?;
+				}
+			)*
+		}
+	};
+}
+
+cache_property_tests! {
+	lru => "lru",
+	lfu => "lfu",
+	fifo => "fifo",
+	two_q => "2q-0.25-0.5",
+	lrfu => "lrfu-2-0.5",
+	sieve => "sieve",
+	s3_fifo => "s3fifo",
+	arc => "arc",
+	lirs => "lirs",
+	gds => "gds",
+	gds_size => "gds-size",
+	gdsf => "gdsf",
+	w_tiny_lfu => "wtinylfu",
+	lru_k => "lruk-2",
+	lru_k_correlated => "lruk-2-5",
+	slru => "slru",
+	slru_three_segments => "slru-0.3-0.3-0.4",
+}
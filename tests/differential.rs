@@ -0,0 +1,327 @@
+/*
+ * Copyright (c) Kia Shakiba
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+//! Differential tests of Kosmo against full simulations. Each test
+//! generates small random traces and runs Kosmo (with no SHARDS and a
+//! reconstruction at every object size) alongside one `Cache` simulation
+//! per size for the same policy, comparing the miss ratios at every
+//! simulated size. Policies which Kosmo models exactly must match at
+//! every size, and on a mismatch the trace is shrunk to a minimal
+//! counterexample, which is reported in the panic message. The others
+//! must stay within a bound on their mean difference.
+
+use std::{slice, str::FromStr};
+
+use kosmo_fast24::{
+	access::{Access, Command, Key},
+	algorithm::Algorithm,
+	cache::CachePolicy,
+	histogram::BUCKET_SIZE,
+	kosmo::{Kosmo, KosmoPolicy},
+};
+
+const NUM_TRACES: u64 = 200;
+const MAX_TRACE_LENGTH: u64 = 120;
+const MAX_NUM_KEYS: u64 = 16;
+
+// reconstructing at every size requires a granularity of at least the
+// number of objects in the trace
+const GRANULARITY: u32 = 1 << 16;
+
+// every object has the size of one histogram bucket, so the sizes at
+// which Kosmo's histogram has points are exactly the sizes which hold a
+// whole number of objects
+const OBJECT_SIZE: u64 = BUCKET_SIZE;
+
+struct Mismatch {
+	size: u64,
+	kosmo_miss_ratio: f64,
+	cache_miss_ratio: f64,
+}
+
+/// A small xorshift generator, so the traces are reproducible.
+struct Generator(u64);
+
+impl Generator {
+	fn new(seed: u64) -> Self {
+		Generator(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+	}
+
+	fn next(&mut self, max: u64) -> u64 {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+
+		self.0 % max
+	}
+}
+
+fn generate_trace(seed: u64) -> Vec<Key> {
+	let mut generator = Generator::new(seed);
+
+	let length = 1 + generator.next(MAX_TRACE_LENGTH);
+	let num_keys = 1 + generator.next(MAX_NUM_KEYS);
+
+	// half of the traces are skewed towards the low keys
+	let skewed = generator.next(2) == 0;
+
+	(0..length)
+		.map(|_| match skewed {
+			true => {
+				let max_key = 1 + generator.next(num_keys);
+				generator.next(max_key)
+			},
+
+			false => generator.next(num_keys),
+		})
+		.collect()
+}
+
+fn to_accesses(keys: &[Key]) -> Vec<Access> {
+	keys.iter()
+		.enumerate()
+		.map(|(index, key)| Access {
+			timestamp: index as u64 + 1,
+			command: Command::Get,
+			key: *key,
+			size: OBJECT_SIZE as u32,
+			ttl: None,
+		})
+		.collect()
+}
+
+/// Returns the miss ratios of Kosmo and of a full simulation at every
+/// size which holds a whole number of the trace's objects, as tuples of
+/// the size and the two miss ratios.
+fn miss_ratios(
+	kosmo_policy: &KosmoPolicy,
+	cache_policy: &CachePolicy,
+	keys: &[Key],
+) -> Vec<(u64, f64, f64)> {
+	let accesses = to_accesses(keys);
+
+	let mut kosmo = Kosmo::new(slice::from_ref(kosmo_policy), None);
+	kosmo.set_granularity(GRANULARITY);
+
+	for access in &accesses {
+		kosmo.handle(access);
+	}
+
+	let curve = kosmo.curve();
+
+	let mut unique_keys = keys.to_vec();
+	unique_keys.sort();
+	unique_keys.dedup();
+
+	(1..=unique_keys.len() as u64)
+		.map(|num_objects| {
+			let size = num_objects * OBJECT_SIZE;
+			let mut cache = cache_policy.new_cache(size);
+
+			for access in &accesses {
+				cache.handle_self_populating(access);
+			}
+
+			(size, curve.get_miss_ratio(size), cache.miss_ratio())
+		})
+		.collect()
+}
+
+/// Returns the first size at which Kosmo's miss ratio differs from that
+/// of a full simulation, if any.
+fn find_mismatch(
+	kosmo_policy: &KosmoPolicy,
+	cache_policy: &CachePolicy,
+	keys: &[Key],
+) -> Option<Mismatch> {
+	miss_ratios(kosmo_policy, cache_policy, keys)
+		.into_iter()
+		.find(|(_, kosmo_miss_ratio, cache_miss_ratio)| (kosmo_miss_ratio - cache_miss_ratio).abs() > 1e-9)
+		.map(|(size, kosmo_miss_ratio, cache_miss_ratio)| Mismatch {
+			size,
+			kosmo_miss_ratio,
+			cache_miss_ratio,
+		})
+}
+
+/// Shrinks a failing trace by repeatedly removing runs of accesses (from
+/// halves of the trace down to single accesses) and merging keys, keeping
+/// any change for which the trace still fails, until no change does.
+fn shrink(keys: &[Key], fails: impl Fn(&[Key]) -> bool) -> Vec<Key> {
+	let mut keys = keys.to_vec();
+	let mut run_length = keys.len().div_ceil(2);
+
+	while run_length > 0 {
+		let mut shrunk = false;
+		let mut start = 0;
+
+		while start < keys.len() {
+			let end = (start + run_length).min(keys.len());
+
+			let candidate = [&keys[..start], &keys[end..]].concat();
+
+			if !candidate.is_empty() && fails(&candidate) {
+				keys = candidate;
+				shrunk = true;
+			} else {
+				start += run_length;
+			}
+		}
+
+		if !shrunk {
+			run_length /= 2;
+		}
+	}
+
+	// replace each key with a smaller one where possible, so the keys of
+	// the counterexample are as few and as small as possible
+	let mut changed = true;
+
+	while changed {
+		changed = false;
+
+		for index in 0..keys.len() {
+			for key in 0..keys[index] {
+				let mut candidate = keys.clone();
+				candidate[index] = key;
+
+				if fails(&candidate) {
+					keys = candidate;
+					changed = true;
+					break;
+				}
+			}
+		}
+	}
+
+	keys
+}
+
+fn parse_policies(name: &str) -> (KosmoPolicy, CachePolicy) {
+	// the Kosmo and cache policy configs are separated by a space if they
+	// differ (e.g., Kosmo's 2Q config does not take parameters)
+	let (kosmo_name, cache_name) = name.split_once(' ').unwrap_or((name, name));
+
+	(
+		KosmoPolicy::from_str(kosmo_name).unwrap(),
+		CachePolicy::from_str(cache_name).unwrap(),
+	)
+}
+
+/// Checks that Kosmo's miss ratio matches that of a full simulation at
+/// every size of every trace.
+fn check_exact_policy(name: &str) {
+	let (kosmo_policy, cache_policy) = parse_policies(name);
+
+	for seed in 0..NUM_TRACES {
+		let keys = generate_trace(seed);
+
+		if find_mismatch(&kosmo_policy, &cache_policy, &keys).is_none() {
+			continue;
+		}
+
+		let fails = |keys: &[Key]| find_mismatch(&kosmo_policy, &cache_policy, keys).is_some();
+		let counterexample = shrink(&keys, fails);
+
+		let mismatch = find_mismatch(&kosmo_policy, &cache_policy, &counterexample).unwrap();
+
+		panic!(
+			"Kosmo ({name}) differs from a full simulation on trace {seed}. Minimal counterexample (keys): {:?}, at a cache size of {} object(s): Kosmo miss ratio {:.4}, simulated miss ratio {:.4}.",
+			counterexample,
+			mismatch.size / OBJECT_SIZE,
+			mismatch.kosmo_miss_ratio,
+			mismatch.cache_miss_ratio,
+		);
+	}
+}
+
+/// Returns the mean difference in miss ratio between Kosmo and a full
+/// simulation over every size of every trace.
+fn mean_error(name: &str) -> f64 {
+	let (kosmo_policy, cache_policy) = parse_policies(name);
+
+	let mut total_error: f64 = 0.0;
+	let mut num_sizes: u64 = 0;
+
+	for seed in 0..NUM_TRACES {
+		let keys = generate_trace(seed);
+
+		for (_, kosmo_miss_ratio, cache_miss_ratio) in miss_ratios(&kosmo_policy, &cache_policy, &keys) {
+			total_error += (kosmo_miss_ratio - cache_miss_ratio).abs();
+			num_sizes += 1;
+		}
+	}
+
+	total_error / num_sizes as f64
+}
+
+// Kosmo counts each access as a hit at every size above its reuse
+// distance, which is exact only if the objects cached at one size are
+// also cached at every larger size. With objects of one size and cost,
+// LRU, GDS (which then orders objects by recency alone) and LRU-K with
+// no correlated reference period keep this inclusion, so they must
+// match a full simulation exactly.
+
+#[test]
+fn lru() {
+	check_exact_policy("lru");
+}
+
+#[test]
+fn gds() {
+	check_exact_policy("gds");
+	check_exact_policy("gds-size");
+}
+
+#[test]
+fn lru_k() {
+	check_exact_policy("lruk-2");
+}
+
+/// The remaining policies are known to be approximate, since an object
+/// which is cached at one size can be evicted at a larger one (e.g.,
+/// FIFO's anomaly), which no single reuse distance can describe. Their
+/// bounds are the mean differences from a full simulation over every
+/// size of every trace, rounded up to a multiple of 0.005, so a change which makes a model less
+/// accurate fails the test.
+#[test]
+fn approximate_policies() {
+	let bounds = [
+		("fifo", 0.025),
+		("lfu", 0.005),
+		("lrfu lrfu-2-0.5", 0.01),
+		("sieve", 0.025),
+		("s3fifo", 0.03),
+		("lirs", 0.025),
+		("gdsf", 0.015),
+		("lruk-2-5", 0.005),
+		("slru", 0.01),
+		("slru-0.3-0.3-0.4", 0.03),
+
+		// Kosmo's 2Q bounds A1in and A1out together by (Kin + Kout) of the
+		// size and otherwise evicts from them only when the cache is full,
+		// whereas the simulated 2Q moves objects from A1in to A1out and
+		// drops them from A1out by their own bounds, even at sizes which
+		// hold every object
+		("2q 2q-0.25-0.5", 0.15),
+	];
+
+	let failures = bounds
+		.iter()
+		.filter_map(|(name, bound)| {
+			let mean_error = mean_error(name);
+
+			(mean_error > *bound).then(|| format!("{name}: {mean_error:.4} (bound {bound})"))
+		})
+		.collect::<Vec<String>>();
+
+	assert!(
+		failures.is_empty(),
+		"The mean difference between Kosmo and a full simulation is above its bound for: {}.",
+		failures.join(", "),
+	);
+}